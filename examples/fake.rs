@@ -1,18 +1,12 @@
-use serde::de::DeserializeOwned;
-use std::time::Duration;
 use update_informer::{
-    http_client::{HeaderMap, HttpClient},
+    http_client::{HttpClient, Request, Response},
     registry, Check,
 };
 
 struct YourOwnHttpClient;
 
 impl HttpClient for YourOwnHttpClient {
-    fn get<T: DeserializeOwned>(
-        _url: &str,
-        _timeout: Duration,
-        _headers: HeaderMap,
-    ) -> update_informer::Result<T> {
+    fn send(&self, _request: Request) -> update_informer::Result<Response> {
         todo!()
     }
 }
@@ -1,21 +1,21 @@
-use isahc::ReadResponseExt;
-use serde::de::DeserializeOwned;
+use isahc::{config::Configurable, ReadResponseExt};
 use std::time::Duration;
 use update_informer::{
-    http_client::{HeaderMap, HttpClient},
+    http_client::{HttpClient, Request, Response},
     registry, Check,
 };
 
 struct YourOwnHttpClient;
 
 impl HttpClient for YourOwnHttpClient {
-    fn get<T: DeserializeOwned>(
-        url: &str,
-        _timeout: Duration,
-        _headers: HeaderMap,
-    ) -> update_informer::Result<T> {
-        let json = isahc::get(url)?.json()?;
-        Ok(json)
+    fn send(&self, request: Request) -> update_informer::Result<Response> {
+        let mut req = isahc::Request::get(request.url).timeout(request.timeout);
+        for (key, value) in request.headers {
+            req = req.header(key, value);
+        }
+
+        let mut resp = isahc::send(req.body(())?)?;
+        Ok(Response::new(resp.status().as_u16(), resp.bytes()?))
     }
 }
 
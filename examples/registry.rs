@@ -14,11 +14,12 @@ impl Registry for YourOwnRegistry {
     const NAME: &'static str = "your_own_registry";
 
     fn get_latest_version<T: HttpClient>(
+        &self,
         http_client: GenericHttpClient<T>,
         _pkg: &Package,
     ) -> Result<Option<String>> {
         let url = "https://turbo.build/api/binaries/version";
-        let resp = http_client.get::<Response>(&url)?;
+        let resp = http_client.get::<Response>(url)?;
 
         Ok(Some(resp.version))
     }
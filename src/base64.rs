@@ -0,0 +1,92 @@
+//! A minimal standard base64 codec used for HTTP basic authentication.
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+pub(crate) fn encode<T: AsRef<[u8]>>(input: T) -> String {
+    let input = input.as_ref();
+    let mut output = String::with_capacity(input.len().div_ceil(3) * 4);
+
+    for chunk in input.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (u32::from(b[0]) << 16) | (u32::from(b[1]) << 8) | u32::from(b[2]);
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                let index = (n >> (18 - i * 6)) & 0x3f;
+                output.push(ALPHABET[index as usize] as char);
+            } else {
+                output.push('=');
+            }
+        }
+    }
+
+    output
+}
+
+#[cfg(feature = "npm")]
+pub(crate) fn decode<T: AsRef<[u8]>>(input: T) -> Option<Vec<u8>> {
+    let input: Vec<u8> = input
+        .as_ref()
+        .iter()
+        .copied()
+        .filter(|b| !b.is_ascii_whitespace())
+        .collect();
+    let input = match input.iter().position(|&b| b == b'=') {
+        Some(pos) if input[pos..].iter().all(|&b| b == b'=') => &input[..pos],
+        Some(_) => return None,
+        None => &input[..],
+    };
+
+    let mut output = Vec::with_capacity(input.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+
+    for &byte in input {
+        let value = ALPHABET.iter().position(|&c| c == byte)? as u32;
+        buffer = (buffer << 6) | value;
+        bits += 6;
+
+        if bits >= 8 {
+            bits -= 8;
+            output.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+
+    Some(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_test() {
+        assert_eq!(encode(""), "");
+        assert_eq!(encode("f"), "Zg==");
+        assert_eq!(encode("fo"), "Zm8=");
+        assert_eq!(encode("foo"), "Zm9v");
+        assert_eq!(encode("user:password"), "dXNlcjpwYXNzd29yZA==");
+    }
+
+    #[test]
+    #[cfg(feature = "npm")]
+    fn decode_test() {
+        assert_eq!(decode("").unwrap(), b"");
+        assert_eq!(decode("Zg==").unwrap(), b"f");
+        assert_eq!(decode("Zm8=").unwrap(), b"fo");
+        assert_eq!(decode("Zm9v").unwrap(), b"foo");
+        assert_eq!(decode("dXNlcjpwYXNzd29yZA==").unwrap(), b"user:password");
+    }
+
+    #[test]
+    #[cfg(feature = "npm")]
+    fn decode_invalid_test() {
+        assert!(decode("Zm9v!").is_none());
+        assert!(decode("Zg=v").is_none());
+    }
+}
@@ -0,0 +1,683 @@
+use crate::{
+    cache::{self, CacheStorage, Clock, FileStorage, SystemClock},
+    fake,
+    http_client::{self, DefaultHttpClient, GenericHttpClient, HttpClient, HttpError, RetryPolicy},
+    offline, Error, Package, Registry, Release, Result, Update, Version, VersionScheme,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    sync::{mpsc, Arc, Mutex},
+    thread,
+    time::{Duration, Instant, UNIX_EPOCH},
+};
+
+const CACHE_KEY: &str = "batch.json";
+
+/// An error that can be sent between threads.
+type SendError = Box<dyn std::error::Error + Send + Sync>;
+
+type CheckFn<H> = Box<
+    dyn FnOnce(GenericHttpClient<'static, H>) -> std::result::Result<Option<Release>, SendError>
+        + Send,
+>;
+
+struct Entry<H: HttpClient> {
+    cache_name: String,
+    name: String,
+    version: String,
+    scheme: VersionScheme,
+    check: CheckFn<H>,
+}
+
+/// A cached release of a package and when it was checked.
+#[derive(Serialize, Deserialize)]
+struct CachedRelease {
+    checked_at: u64,
+    release: Release,
+}
+
+/// Checks many packages at once, e.g. the installed plugins of an application.
+///
+/// Requests are sent concurrently using one HTTP client, by at most
+/// [`concurrency()`](Self::concurrency) threads, and must complete before a common deadline. The releases of all packages are cached in a single file, so each package
+/// is only checked once per [`interval()`](Self::interval).
+///
+/// # Examples
+///
+/// ```rust
+/// use std::time::Duration;
+/// use update_informer::registry;
+///
+/// let results = update_informer::batch()
+///     .add(registry::Crates, "plugin-a", "0.1.0")
+///     .add(registry::CratesIndex, "plugin-b", "1.2.0")
+///     .deadline(Duration::from_secs(3))
+///     .check_versions();
+///
+/// for result in results {
+///     if let Ok(Some(version)) = result {
+///         println!("New version is available: {}", version);
+///     }
+/// }
+/// ```
+pub struct Batch<H: HttpClient = DefaultHttpClient> {
+    entries: Vec<Entry<H>>,
+    http_client: H,
+    interval: Duration,
+    deadline: Duration,
+    concurrency: usize,
+    retry_policy: RetryPolicy,
+    user_agent: Option<String>,
+    max_body_size: u64,
+    cache_storage: Box<dyn CacheStorage>,
+    clock: Box<dyn Clock>,
+}
+
+impl<H> Batch<H>
+where
+    H: HttpClient + Send + Sync + 'static,
+{
+    /// Constructs a new `Batch`.
+    ///
+    /// # Arguments
+    ///
+    /// * `http_client` - A type that implements the `HttpClient` trait, shared by all requests.
+    pub fn new(http_client: H) -> Self {
+        Self {
+            entries: Vec::new(),
+            http_client,
+            interval: Duration::from_secs(60 * 60 * 24), // Once a day
+            deadline: Duration::from_secs(5),
+            concurrency: 8,
+            retry_policy: RetryPolicy::default(),
+            user_agent: None,
+            max_body_size: http_client::DEFAULT_MAX_BODY_SIZE,
+            cache_storage: Box::new(FileStorage::new()),
+            clock: Box::new(SystemClock),
+        }
+    }
+
+    /// Adds a package to check.
+    ///
+    /// # Arguments
+    ///
+    /// * `registry` - A registry service such as Crates.io or GitHub.
+    /// * `name` - A project name.
+    /// * `version` - Current version of the project.
+    pub fn add<R, N, V>(mut self, registry: R, name: N, version: V) -> Self
+    where
+        R: Registry + Send + 'static,
+        N: Into<String>,
+        V: Into<String>,
+    {
+        let name = name.into();
+        let version = version.into();
+        let scheme = registry.version_scheme();
+        let cache_name = registry.cache_name();
+
+        let (pkg_name, pkg_version) = (name.clone(), version.clone());
+        let check: CheckFn<H> = Box::new(move |client| {
+            let check = || {
+                let pkg = Package::with_scheme(&pkg_name, &pkg_version, scheme)?;
+                registry.get_latest_release(client, &pkg)
+            };
+
+            // Errors are sent between threads, so only an `HttpError` is kept as it is
+            check().map_err(|e| match e.downcast::<HttpError>() {
+                Ok(error) => error as SendError,
+                Err(e) => e.to_string().into(),
+            })
+        });
+
+        self.entries.push(Entry {
+            cache_name,
+            name,
+            version,
+            scheme,
+            check,
+        });
+        self
+    }
+
+    /// Sets the interval of how often to check each package for a new version.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - 24 hours by default. Specify `Duration::ZERO` to work without a cache file.
+    pub fn interval(self, interval: Duration) -> Self {
+        Self { interval, ..self }
+    }
+
+    /// Sets the deadline for all requests.
+    ///
+    /// # Arguments
+    ///
+    /// * `deadline` - By default, it is 5 seconds. Packages that are not checked before the
+    ///   deadline get an error.
+    pub fn deadline(self, deadline: Duration) -> Self {
+        Self { deadline, ..self }
+    }
+
+    /// Sets the maximum number of packages checked at the same time.
+    ///
+    /// # Arguments
+    ///
+    /// * `concurrency` - By default, 8. Each package being checked takes a thread, so specify
+    ///   a lower number to limit the threads and the load on the registries.
+    pub fn concurrency(self, concurrency: usize) -> Self {
+        Self {
+            concurrency: concurrency.max(1),
+            ..self
+        }
+    }
+
+    /// Sets a policy to retry requests that fail with a transient error, e.g. 502.
+    ///
+    /// # Arguments
+    ///
+    /// * `retry_policy` - By default, a request is sent at most 3 times before the deadline.
+    pub fn retry_policy(self, retry_policy: RetryPolicy) -> Self {
+        Self {
+            retry_policy,
+            ..self
+        }
+    }
+
+    /// Sets the User-Agent sent to the registries.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_agent` - By default, it identifies the first package and `update-informer`,
+    ///   e.g. `plugin-a/0.1.0 update-informer/1.3.0`. Specify it to identify the application
+    ///   instead, e.g. `my-app/0.1.0 (https://example.com)`.
+    pub fn user_agent<U: Into<String>>(self, user_agent: U) -> Self {
+        Self {
+            user_agent: Some(user_agent.into()),
+            ..self
+        }
+    }
+
+    /// Sets the maximum size of responses from the registries.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_body_size` - The maximum size of a response body in bytes. By default, it is 16 MiB.
+    pub fn max_body_size(self, max_body_size: u64) -> Self {
+        Self {
+            max_body_size,
+            ..self
+        }
+    }
+
+    /// Sets a storage of the cached releases.
+    ///
+    /// # Arguments
+    ///
+    /// * `cache_storage` - A type that implements the `CacheStorage` trait. By default, it is
+    ///   a file in the cache directory.
+    pub fn cache_storage<S: CacheStorage + 'static>(self, cache_storage: S) -> Self {
+        Self {
+            cache_storage: Box::new(cache_storage),
+            ..self
+        }
+    }
+
+    /// Sets a clock to check whether the interval of each package has expired.
+    ///
+    /// # Arguments
+    ///
+    /// * `clock` - A type that implements the `Clock` trait. By default, it is the system clock.
+    pub fn clock<C: Clock + 'static>(self, clock: C) -> Self {
+        Self {
+            clock: Box::new(clock),
+            ..self
+        }
+    }
+
+    /// Checks all packages for new versions, returning a result per package in the order they were added.
+    pub fn check_versions(self) -> Vec<Result<Option<Version>>> {
+        self.check_updates()
+            .into_iter()
+            .map(|result| result.map(|update| update.map(Version::from)))
+            .collect()
+    }
+
+    /// Checks all packages for new versions together with the release details, returning
+    /// a result per package in the order they were added.
+    ///
+    /// Requests that are still running at the deadline are not waited for.
+    pub fn check_updates(self) -> Vec<Result<Option<Update>>> {
+        // Integration tests of applications can force the outcome of all checks
+        match fake::Outcome::from_env() {
+            Ok(Some(outcome)) => return self.check_fake(outcome),
+            Ok(None) => {}
+            Err(e) => {
+                let message = e.to_string();
+                return self
+                    .entries
+                    .iter()
+                    .map(|_| Err(message.as_str().into()))
+                    .collect();
+            }
+        }
+
+        if offline::is_offline() {
+            return self.entries.iter().map(|_| Ok(None)).collect();
+        }
+
+        let use_cache = !self.interval.is_zero();
+        let mut cache = if use_cache {
+            read_cache(self.cache_storage.as_ref())
+        } else {
+            HashMap::new()
+        };
+        let now = unix_time(self.clock.as_ref());
+
+        let mut packages = Vec::new();
+        let mut releases: Vec<Option<Result<Option<Release>>>> = Vec::new();
+        let mut pending = Vec::new();
+
+        for (index, entry) in self.entries.into_iter().enumerate() {
+            packages.push((entry.name, entry.version, entry.scheme));
+            let (name, version, scheme) = &packages[index];
+
+            let key = match Package::with_scheme(name, version, *scheme) {
+                Ok(pkg) => format!("{}-{}", entry.cache_name, pkg.name()),
+                Err(e) => {
+                    releases.push(Some(Err(e)));
+                    continue;
+                }
+            };
+
+            if use_cache {
+                let checked_at = cache.get(&key).map(|cached| cached.checked_at);
+
+                match checked_at {
+                    Some(checked_at)
+                        if now.saturating_sub(checked_at) < self.interval.as_secs() =>
+                    {
+                        let release = cache[&key].release.clone();
+                        releases.push(Some(Ok(Some(release))));
+                        continue;
+                    }
+                    // The first check will start only after the interval has expired
+                    None => {
+                        cache.insert(key, CachedRelease::new(now, Release::new(version)));
+                        releases.push(Some(Ok(None)));
+                        continue;
+                    }
+                    // Until a new release is found, the package is not checked again for an interval
+                    Some(_) => {
+                        cache.insert(key.clone(), CachedRelease::new(now, Release::new(version)));
+                    }
+                }
+            }
+
+            releases.push(None);
+            pending.push((index, key, entry.check));
+        }
+
+        let user_agent = match (self.user_agent, packages.first()) {
+            (Some(user_agent), _) => user_agent,
+            (None, Some((name, version, _))) => http_client::app_user_agent(name, version),
+            (None, None) => http_client::default_user_agent(),
+        };
+        let client = http_client::new(self.http_client, self.deadline)
+            .retry_policy(self.retry_policy)
+            .user_agent(user_agent)
+            .max_body_size(self.max_body_size);
+        let (tx, rx) = mpsc::channel();
+        let mut keys = HashMap::new();
+        let mut checks = Vec::new();
+
+        for (index, key, check) in pending {
+            keys.insert(index, key);
+            checks.push((index, check));
+        }
+
+        // Workers take the next package from a shared queue until it is empty
+        let queue = Arc::new(Mutex::new(checks.into_iter()));
+        for _ in 0..self.concurrency.min(keys.len()) {
+            let tx = tx.clone();
+            let client = client.clone();
+            let queue = Arc::clone(&queue);
+
+            thread::spawn(move || loop {
+                let next = queue.lock().unwrap_or_else(|e| e.into_inner()).next();
+                let Some((index, check)) = next else {
+                    break;
+                };
+
+                // The receiver is gone if the deadline has passed
+                if tx.send((index, check(client.clone()))).is_err() {
+                    break;
+                }
+            });
+        }
+
+        let started_at = Instant::now();
+        for _ in 0..keys.len() {
+            let timeout = self.deadline.saturating_sub(started_at.elapsed());
+            let Ok((index, result)) = rx.recv_timeout(timeout) else {
+                break;
+            };
+
+            if let Ok(Some(release)) = &result {
+                cache.insert(
+                    keys[&index].clone(),
+                    CachedRelease::new(now, release.clone()),
+                );
+            }
+            releases[index] = Some(result.map_err(|e| e as Error));
+        }
+
+        if use_cache {
+            // The results are still returned if the cache file cannot be written
+            let _ = write_cache(self.cache_storage.as_ref(), self.clock.as_ref(), &cache);
+        }
+
+        releases
+            .into_iter()
+            .zip(packages)
+            .map(|(release, (name, version, scheme))| {
+                let release = match release {
+                    Some(Ok(Some(release))) => release,
+                    Some(Ok(None)) => return Ok(None),
+                    Some(Err(e)) => return Err(e),
+                    None => return Err(HttpError::DeadlineExceeded.into()),
+                };
+
+                let pkg = Package::with_scheme(&name, &version, scheme)?;
+                Update::check(&pkg, release, scheme)
+            })
+            .collect()
+    }
+
+    fn check_fake(self, outcome: fake::Outcome) -> Vec<Result<Option<Update>>> {
+        let waited =
+            fake::latency_from_env().and_then(|latency| fake::wait(latency, self.deadline));
+
+        self.entries
+            .iter()
+            .map(|entry| match &waited {
+                Ok(()) => outcome.clone().check(&entry.version, entry.scheme),
+                Err(e) => match e.downcast_ref::<HttpError>() {
+                    Some(error) => Err(error.clone().into()),
+                    None => Err(e.to_string().into()),
+                },
+            })
+            .collect()
+    }
+}
+
+impl CachedRelease {
+    fn new(checked_at: u64, release: Release) -> Self {
+        Self {
+            checked_at,
+            release,
+        }
+    }
+}
+
+/// Reads the cached releases, starting over if they are missing or cannot be parsed.
+fn read_cache(storage: &dyn CacheStorage) -> HashMap<String, CachedRelease> {
+    let read = || -> Result<HashMap<String, CachedRelease>> {
+        let Some(entry) = storage.read(CACHE_KEY)? else {
+            return Ok(HashMap::new());
+        };
+
+        Ok(serde_json::from_str(&entry.contents)?)
+    };
+
+    read().unwrap_or_default()
+}
+
+fn write_cache(
+    storage: &dyn CacheStorage,
+    clock: &dyn Clock,
+    cache: &HashMap<String, CachedRelease>,
+) -> Result<()> {
+    let entry = cache::CacheEntry::new(serde_json::to_string(cache)?, clock.now());
+    storage.write(CACHE_KEY, entry)
+}
+
+fn unix_time(clock: &dyn Clock) -> u64 {
+    clock
+        .now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cache::MemoryStorage,
+        registry::Crates,
+        test_helper::{mock_crates, read_globals},
+        testing::{MockClock, MockHttpClient},
+    };
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    const CURRENT_VERSION: &str = "3.1.0";
+    const LATEST_VERSION: &str = "3.1.1";
+    const FIXTURE: &str = "tests/fixtures/registry/crates/versions.json";
+    const DAY: Duration = Duration::from_secs(60 * 60 * 24);
+
+    /// A registry that answers after a delay.
+    struct Slow;
+
+    impl Registry for Slow {
+        const NAME: &'static str = "slow";
+
+        fn get_latest_version<T: HttpClient>(
+            &self,
+            _http_client: GenericHttpClient<T>,
+            _pkg: &Package,
+        ) -> Result<Option<String>> {
+            thread::sleep(Duration::from_secs(1));
+
+            Ok(Some("9.0.0".to_string()))
+        }
+    }
+
+    /// A registry recording how many packages are checked at the same time.
+    #[derive(Clone, Default)]
+    struct Counting {
+        running: Arc<AtomicUsize>,
+        max_running: Arc<AtomicUsize>,
+    }
+
+    impl Registry for Counting {
+        const NAME: &'static str = "counting";
+
+        fn get_latest_version<T: HttpClient>(
+            &self,
+            _http_client: GenericHttpClient<T>,
+            _pkg: &Package,
+        ) -> Result<Option<String>> {
+            let running = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_running.fetch_max(running, Ordering::SeqCst);
+            thread::sleep(Duration::from_millis(50));
+            self.running.fetch_sub(1, Ordering::SeqCst);
+
+            Ok(Some("9.0.0".to_string()))
+        }
+    }
+
+    fn clock() -> MockClock {
+        MockClock::new(UNIX_EPOCH + DAY * 365)
+    }
+
+    fn write_cache_entry(storage: &MemoryStorage, key: &str, checked_at: u64, version: &str) {
+        let mut cache = HashMap::new();
+        cache.insert(
+            key.to_string(),
+            CachedRelease::new(checked_at, Release::new(version)),
+        );
+
+        write_cache(storage, &SystemClock, &cache).expect("write cache");
+    }
+
+    #[test]
+    fn first_check_test() {
+        let _globals = read_globals();
+        let pkg = Package::new("batch-first", CURRENT_VERSION).unwrap();
+        let (mock, _) = mock_crates(&pkg, 200, FIXTURE);
+        let mock = mock.expect(0);
+        let storage = MemoryStorage::new();
+
+        let results = crate::batch()
+            .add(Crates, "batch-first", CURRENT_VERSION)
+            .cache_storage(storage.clone())
+            .check_versions();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].as_ref().unwrap(), &None);
+        assert!(read_cache(&storage).contains_key("crates-batch-first"));
+        mock.assert();
+    }
+
+    #[test]
+    fn without_cache_test() {
+        let _globals = read_globals();
+        let pkg = Package::new("batch-found", CURRENT_VERSION).unwrap();
+        let (_found, _) = mock_crates(&pkg, 200, FIXTURE);
+        let pkg = Package::new("batch-missing", CURRENT_VERSION).unwrap();
+        let (_missing, _) = mock_crates(&pkg, 404, "tests/fixtures/registry/crates/not_found.json");
+        let storage = MemoryStorage::new();
+
+        let results = crate::batch()
+            .add(Crates, "batch-found", CURRENT_VERSION)
+            .add(Crates, "batch-missing", CURRENT_VERSION)
+            .add(Crates, "batch-invalid", "not a version")
+            .interval(Duration::ZERO)
+            .cache_storage(storage.clone())
+            .check_versions();
+        let version = Version::parse(LATEST_VERSION).expect("parse version");
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].as_ref().unwrap(), &Some(version));
+        assert_eq!(
+            results[1].as_ref().unwrap_err().downcast_ref(),
+            Some(&HttpError::Status(404))
+        );
+        assert!(results[2].is_err());
+        assert!(read_cache(&storage).is_empty());
+    }
+
+    #[test]
+    fn expired_cache_test() {
+        let _globals = read_globals();
+        let pkg = Package::new("batch-expired", CURRENT_VERSION).unwrap();
+        let (_mock, _) = mock_crates(&pkg, 200, FIXTURE);
+        let (storage, clock) = (MemoryStorage::new(), clock());
+        let checked_at = unix_time(&clock) - DAY.as_secs();
+        write_cache_entry(
+            &storage,
+            "crates-batch-expired",
+            checked_at,
+            CURRENT_VERSION,
+        );
+
+        let results = crate::batch()
+            .add(Crates, "batch-expired", CURRENT_VERSION)
+            .cache_storage(storage.clone())
+            .clock(clock)
+            .check_updates();
+        let update = results[0].as_ref().unwrap().as_ref().expect("get update");
+
+        assert_eq!(update.release(), &Release::new(LATEST_VERSION));
+        assert_eq!(
+            read_cache(&storage)["crates-batch-expired"].release,
+            Release::new(LATEST_VERSION)
+        );
+    }
+
+    #[test]
+    fn cached_release_test() {
+        let _globals = read_globals();
+        let pkg = Package::new("batch-cached", CURRENT_VERSION).unwrap();
+        let (mock, _) = mock_crates(&pkg, 200, FIXTURE);
+        let mock = mock.expect(0);
+        let (storage, clock) = (MemoryStorage::new(), clock());
+        let checked_at = unix_time(&clock) - DAY.as_secs() + 1;
+        write_cache_entry(&storage, "crates-batch-cached", checked_at, "4.0.0");
+
+        let results = crate::batch()
+            .add(Crates, "batch-cached", CURRENT_VERSION)
+            .cache_storage(storage)
+            .clock(clock)
+            .check_versions();
+        let version = Version::parse("4.0.0").expect("parse version");
+
+        assert_eq!(results[0].as_ref().unwrap(), &Some(version));
+        mock.assert();
+    }
+
+    #[test]
+    fn deadline_test() {
+        let _globals = read_globals();
+        let pkg = Package::new("batch-fast", CURRENT_VERSION).unwrap();
+        let (_mock, _) = mock_crates(&pkg, 200, FIXTURE);
+
+        let started_at = Instant::now();
+        let results = crate::batch()
+            .add(Slow, "batch-slow", CURRENT_VERSION)
+            .add(Crates, "batch-fast", CURRENT_VERSION)
+            .interval(Duration::ZERO)
+            .deadline(Duration::from_millis(300))
+            .check_versions();
+
+        assert!(started_at.elapsed() < Duration::from_secs(1));
+        assert_eq!(
+            results[0].as_ref().unwrap_err().to_string(),
+            "deadline exceeded"
+        );
+        assert!(results[1].as_ref().unwrap().is_some());
+    }
+
+    #[test]
+    fn concurrency_test() {
+        let _globals = read_globals();
+        let registry = Counting::default();
+        let mut batch = crate::batch().interval(Duration::ZERO).concurrency(2);
+        for index in 0..6 {
+            batch = batch.add(registry.clone(), format!("batch-{index}"), CURRENT_VERSION);
+        }
+
+        let results = batch.check_versions();
+
+        assert!(results.iter().all(|result| result.is_ok()));
+        assert_eq!(registry.max_running.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn user_agent_test() {
+        let _globals = read_globals();
+        let check = |user_agent: Option<&str>| {
+            let client = MockHttpClient::new();
+            let mut batch = Batch::new(client.clone())
+                .add(Crates, "plugin-a", "0.1.0")
+                .add(Crates, "plugin-b", "0.2.0")
+                .interval(Duration::ZERO);
+            if let Some(user_agent) = user_agent {
+                batch = batch.user_agent(user_agent);
+            }
+            batch.check_versions();
+
+            let requests = client.requests();
+            assert_eq!(requests.len(), 2);
+            requests[0].header("User-Agent").map(str::to_string)
+        };
+
+        let expected = http_client::app_user_agent("plugin-a", "0.1.0");
+        assert_eq!(check(None), Some(expected));
+        assert_eq!(
+            check(Some("my-app/1.0.0")),
+            Some("my-app/1.0.0".to_string())
+        );
+    }
+}
@@ -0,0 +1,212 @@
+use crate::Result;
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::ErrorKind,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::SystemTime,
+};
+
+/// A value in the cache and when it was written.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CacheEntry {
+    /// The cached value, e.g. the latest version.
+    pub contents: String,
+    /// The time of the [`Clock`] when the value was written.
+    pub modified: SystemTime,
+}
+
+impl CacheEntry {
+    /// Constructs a new `CacheEntry`.
+    pub fn new<C: Into<String>>(contents: C, modified: SystemTime) -> Self {
+        Self {
+            contents: contents.into(),
+            modified,
+        }
+    }
+}
+
+/// A storage of cached versions, used to check for a new version only once per interval.
+///
+/// Keys are suitable for file names, e.g. `crates-my-app`.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::{collections::HashMap, sync::Mutex};
+/// use update_informer::cache::{CacheEntry, CacheStorage};
+///
+/// #[derive(Default)]
+/// struct YourOwnStorage(Mutex<HashMap<String, CacheEntry>>);
+///
+/// impl CacheStorage for YourOwnStorage {
+///     fn read(&self, key: &str) -> update_informer::Result<Option<CacheEntry>> {
+///         Ok(self.0.lock().unwrap().get(key).cloned())
+///     }
+///
+///     fn write(&self, key: &str, entry: CacheEntry) -> update_informer::Result<()> {
+///         self.0.lock().unwrap().insert(key.to_string(), entry);
+///         Ok(())
+///     }
+/// }
+/// ```
+pub trait CacheStorage: Send + Sync {
+    /// Returns the entry of the key, or `None` if nothing is cached yet.
+    fn read(&self, key: &str) -> Result<Option<CacheEntry>>;
+
+    /// Replaces the entry of the key.
+    fn write(&self, key: &str, entry: CacheEntry) -> Result<()>;
+}
+
+/// A source of the current time, used to check whether the interval has expired.
+pub trait Clock: Send + Sync {
+    /// Returns the current time.
+    fn now(&self) -> SystemTime;
+}
+
+/// The system clock, used by default.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// A storage of a file per key in a directory, used by default.
+///
+/// The time an entry was written is stored as the modification time of its file.
+#[derive(Clone, Debug, Default)]
+pub struct FileStorage {
+    dir: Option<PathBuf>,
+}
+
+impl FileStorage {
+    /// Constructs a new `FileStorage` in the `update-informer-rs` directory of the user's cache directory.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Constructs a new `FileStorage` in the directory, which is created on the first write.
+    pub fn with_dir<P: Into<PathBuf>>(dir: P) -> Self {
+        Self {
+            dir: Some(dir.into()),
+        }
+    }
+
+    fn dir(&self) -> Result<PathBuf> {
+        match &self.dir {
+            Some(dir) => Ok(dir.clone()),
+            None => default_dir(),
+        }
+    }
+}
+
+impl CacheStorage for FileStorage {
+    fn read(&self, key: &str) -> Result<Option<CacheEntry>> {
+        let path = self.dir()?.join(key);
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        let modified = fs::metadata(&path)?.modified()?;
+        Ok(Some(CacheEntry::new(contents, modified)))
+    }
+
+    fn write(&self, key: &str, entry: CacheEntry) -> Result<()> {
+        let dir = self.dir()?;
+        fs::create_dir_all(&dir)?;
+
+        let path = dir.join(key);
+        fs::write(&path, entry.contents)?;
+        File::options()
+            .write(true)
+            .open(&path)?
+            .set_modified(entry.modified)?;
+
+        Ok(())
+    }
+}
+
+fn default_dir() -> Result<PathBuf> {
+    use etcetera::BaseStrategy;
+    let base_dir =
+        etcetera::choose_base_strategy().map_err(|_| "Unable to find cache directory")?;
+    Ok(base_dir.cache_dir().join("update-informer-rs"))
+}
+
+/// A storage in memory, e.g. to check once per interval within a long-running process
+/// without writing files, or to test an application.
+///
+/// Clones share the entries.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryStorage {
+    entries: Arc<Mutex<HashMap<String, CacheEntry>>>,
+}
+
+impl MemoryStorage {
+    /// Constructs a new empty `MemoryStorage`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl CacheStorage for MemoryStorage {
+    fn read(&self, key: &str) -> Result<Option<CacheEntry>> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+
+        Ok(entries.get(key).cloned())
+    }
+
+    fn write(&self, key: &str, entry: CacheEntry) -> Result<()> {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.insert(key.to_string(), entry);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn file_storage_test() {
+        let dir =
+            std::env::temp_dir().join(format!("update-informer-cache-{}", std::process::id()));
+        let storage = FileStorage::with_dir(&dir);
+        assert_eq!(storage.read("crates-repo").unwrap(), None);
+
+        let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        storage
+            .write("crates-repo", CacheEntry::new("0.1.0", modified))
+            .expect("write entry");
+        assert_eq!(
+            fs::read_to_string(dir.join("crates-repo")).unwrap(),
+            "0.1.0"
+        );
+
+        let entry = storage.read("crates-repo").unwrap();
+        fs::remove_dir_all(&dir).expect("remove test dir");
+        assert_eq!(entry, Some(CacheEntry::new("0.1.0", modified)));
+    }
+
+    #[test]
+    fn memory_storage_test() {
+        let storage = MemoryStorage::new();
+        assert_eq!(storage.read("crates-repo").unwrap(), None);
+
+        let entry = CacheEntry::new("0.1.0", SystemTime::UNIX_EPOCH);
+        storage
+            .clone()
+            .write("crates-repo", entry.clone())
+            .expect("write entry");
+        assert_eq!(storage.read("crates-repo").unwrap(), Some(entry));
+    }
+}
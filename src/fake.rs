@@ -0,0 +1,128 @@
+use crate::{http_client::HttpError, Release, Result, Update, Version, VersionScheme};
+use std::{env, thread, time::Duration};
+
+/// An outcome of a fake check, set by [`FakeUpdateInformer`](crate::FakeUpdateInformer)
+/// or forced by the `UPDATE_INFORMER_FAKE` environment variable.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Outcome {
+    /// A new version, or the next patch version if not specified.
+    Update(Option<String>),
+    NoUpdate,
+    Error(HttpError),
+}
+
+impl Outcome {
+    /// Reads the outcome forced by `UPDATE_INFORMER_FAKE`, if set:
+    ///
+    /// * `update` or `update:2.0.0` - a new version, the next patch version by default;
+    /// * `none` - no new version;
+    /// * `error` - a connection error, or `error:503` - an unsuccessful status;
+    /// * `html` - an HTML page, e.g. from a captive portal;
+    /// * `timeout` - the deadline is exceeded.
+    pub(crate) fn from_env() -> Result<Option<Self>> {
+        let Ok(value) = env::var("UPDATE_INFORMER_FAKE") else {
+            return Ok(None);
+        };
+
+        let (mode, arg) = match value.trim().split_once(':') {
+            Some((mode, arg)) => (mode, Some(arg.trim())),
+            None => (value.trim(), None),
+        };
+
+        let outcome = match (mode, arg) {
+            ("", None) => return Ok(None),
+            ("update", version) => Self::Update(version.map(String::from)),
+            ("none", None) => Self::NoUpdate,
+            ("error", None) => Self::Error(HttpError::Connect("connection refused".to_string())),
+            ("error", Some(status)) => {
+                let status = status.parse().map_err(|_| {
+                    format!("invalid HTTP status in UPDATE_INFORMER_FAKE: {status}")
+                })?;
+                Self::Error(HttpError::Status(status))
+            }
+            ("html", None) => Self::Error(HttpError::Html),
+            ("timeout", None) => Self::Error(HttpError::DeadlineExceeded),
+            _ => return Err(format!("invalid UPDATE_INFORMER_FAKE: {value}").into()),
+        };
+
+        Ok(Some(outcome))
+    }
+
+    /// Returns the result of a check of the current version.
+    pub(crate) fn check(
+        self,
+        current_version: &str,
+        scheme: VersionScheme,
+    ) -> Result<Option<Update>> {
+        let new_version = match self {
+            Self::Update(Some(version)) => version,
+            Self::Update(None) => {
+                let current = Version::parse_with_scheme(current_version, scheme)?;
+                let semver = current.semver();
+                format!("{}.{}.{}", semver.major, semver.minor, semver.patch + 1)
+            }
+            Self::NoUpdate => return Ok(None),
+            Self::Error(error) => return Err(error.into()),
+        };
+
+        let version = Version::parse_with_scheme(&new_version, scheme)?;
+        Ok(Some(Update::new(version, Release::new(new_version), false)))
+    }
+}
+
+/// Reads the latency of fake checks set by `UPDATE_INFORMER_FAKE_LATENCY` in milliseconds.
+pub(crate) fn latency_from_env() -> Result<Duration> {
+    let Ok(value) = env::var("UPDATE_INFORMER_FAKE_LATENCY") else {
+        return Ok(Duration::ZERO);
+    };
+
+    let millis = value
+        .trim()
+        .parse()
+        .map_err(|_| format!("invalid UPDATE_INFORMER_FAKE_LATENCY: {value}"))?;
+    Ok(Duration::from_millis(millis))
+}
+
+/// Waits for the latency, failing like a real check if it exceeds the timeout.
+pub(crate) fn wait(latency: Duration, timeout: Duration) -> Result<()> {
+    if latency > timeout {
+        thread::sleep(timeout);
+        return Err(HttpError::DeadlineExceeded.into());
+    }
+
+    thread::sleep(latency);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_test() {
+        let scheme = VersionScheme::Semver;
+
+        let update = Outcome::Update(None).check("1.2.3", scheme).unwrap();
+        assert_eq!(update.unwrap().version().to_string(), "v1.2.4");
+
+        let update = Outcome::Update(Some("2.0.0".to_string()))
+            .check("1.2.3", scheme)
+            .unwrap();
+        assert_eq!(update.unwrap().version().to_string(), "v2.0.0");
+
+        assert_eq!(Outcome::NoUpdate.check("1.2.3", scheme).unwrap(), None);
+
+        let error = Outcome::Error(HttpError::Status(503))
+            .check("1.2.3", scheme)
+            .unwrap_err();
+        assert_eq!(error.downcast_ref(), Some(&HttpError::Status(503)));
+    }
+
+    #[test]
+    fn wait_test() {
+        assert!(wait(Duration::from_millis(10), Duration::from_secs(1)).is_ok());
+
+        let error = wait(Duration::from_secs(1), Duration::from_millis(10)).unwrap_err();
+        assert_eq!(error.downcast_ref(), Some(&HttpError::DeadlineExceeded));
+    }
+}
@@ -0,0 +1,46 @@
+use std::fmt;
+
+/// An error returned by [`GenericHttpClient`](crate::http_client::GenericHttpClient)
+/// for a response that cannot be used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum HttpError {
+    /// The response has an unsuccessful status, e.g. 404.
+    Status(u16),
+    /// The deadline of the check was exceeded before the request was sent.
+    DeadlineExceeded,
+    /// The response body is larger than the maximum size in bytes.
+    BodyTooLarge(u64),
+    /// The response is an HTML page, e.g. from a captive portal or a proxy login page.
+    Html,
+    /// The connection failed, was reset or timed out, so the request may succeed if retried.
+    Connect(String),
+}
+
+impl HttpError {
+    /// Returns the HTTP status of the response, if any.
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            Self::Status(status) => Some(*status),
+            Self::DeadlineExceeded | Self::BodyTooLarge(_) | Self::Html | Self::Connect(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for HttpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Status(status) => write!(f, "unsuccessful HTTP status: {status}"),
+            Self::DeadlineExceeded => f.write_str("deadline exceeded"),
+            Self::BodyTooLarge(max) => {
+                write!(f, "response body exceeds the maximum size of {max} bytes")
+            }
+            Self::Html => f.write_str(
+                "received an HTML page instead of the expected response, e.g. from a captive portal",
+            ),
+            Self::Connect(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for HttpError {}
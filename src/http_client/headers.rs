@@ -1,6 +1,6 @@
 use std::collections::{hash_map::IntoIter, HashMap};
 
-#[derive(Default)]
+#[derive(Clone, Debug, Default)]
 /// A set of HTTP headers
 pub struct HeaderMap<'a> {
     inner: HashMap<&'a str, &'a str>,
@@ -14,6 +14,18 @@ impl<'a> HeaderMap<'a> {
     pub(crate) fn add(&mut self, key: &'a str, value: &'a str) {
         self.inner.insert(key, value);
     }
+
+    /// Returns the value of a header, ignoring the case of its name.
+    pub fn get(&self, key: &str) -> Option<&'a str> {
+        self.iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v)
+    }
+
+    /// Returns an iterator over the headers.
+    pub fn iter(&self) -> impl Iterator<Item = (&'a str, &'a str)> + '_ {
+        self.inner.iter().map(|(k, v)| (*k, *v))
+    }
 }
 
 impl<'a> IntoIterator for HeaderMap<'a> {
@@ -1,6 +1,10 @@
 use crate::Result;
 use serde::de::DeserializeOwned;
-use std::time::Duration;
+use std::{
+    sync::Arc,
+    thread,
+    time::{Duration, Instant},
+};
 
 #[cfg(feature = "ureq")]
 mod ureq;
@@ -23,21 +27,131 @@ pub use crate::http_client::undefined::UndefinedHttpClient;
 #[cfg(all(not(feature = "ureq"), not(feature = "reqwest")))]
 pub type DefaultHttpClient = UndefinedHttpClient;
 
+mod error;
+pub use error::HttpError;
+
 mod headers;
 pub use headers::HeaderMap;
 
+mod retry;
+pub use retry::RetryPolicy;
+
+/// The default maximum size of response bodies, 16 MiB.
+pub const DEFAULT_MAX_BODY_SIZE: u64 = 16 * 1024 * 1024;
+
+/// An HTTP request to the registry.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct Request<'a> {
+    /// The URL to send a GET request to.
+    pub url: &'a str,
+    /// The maximum time to wait for the response.
+    pub timeout: Duration,
+    /// Additional request headers.
+    pub headers: HeaderMap<'a>,
+    /// The maximum size of the response body in bytes. Larger responses must be errors,
+    /// preferably before the body is read completely.
+    pub max_body_size: u64,
+}
+
+impl<'a> Request<'a> {
+    pub(crate) fn new(url: &'a str, timeout: Duration, headers: HeaderMap<'a>) -> Self {
+        Self {
+            url,
+            timeout,
+            headers,
+            max_body_size: DEFAULT_MAX_BODY_SIZE,
+        }
+    }
+}
+
+/// An HTTP response from the registry.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Response {
+    /// The status code, e.g. 200.
+    pub status: u16,
+    /// The response headers.
+    pub headers: Vec<(String, String)>,
+    /// The response body.
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    /// Constructs a new `Response` without headers.
+    pub fn new<B: Into<Vec<u8>>>(status: u16, body: B) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: body.into(),
+        }
+    }
+
+    /// Adds a response header.
+    pub fn with_header<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.headers.push((key.into(), value.into()));
+        self
+    }
+
+    /// Returns the value of a header, ignoring the case of its name.
+    pub fn header(&self, key: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Returns `true` if the response is an HTML page, judging by the `Content-Type` header
+    /// or the beginning of the body.
+    pub fn is_html(&self) -> bool {
+        let content_type = self.header("Content-Type").unwrap_or_default();
+        if content_type.starts_with("text/html") || content_type.starts_with("application/xhtml") {
+            return true;
+        }
+
+        let start = String::from_utf8_lossy(&self.body[..self.body.len().min(64)]);
+        let start = start.trim_start().to_ascii_lowercase();
+
+        start.starts_with("<!doctype html") || start.starts_with("<html")
+    }
+
+    /// Returns `true` if the status is in the range 200-299.
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// Returns the body as text.
+    pub fn text(&self) -> Result<String> {
+        Ok(String::from_utf8(self.body.clone())?)
+    }
+
+    /// Deserializes the body from JSON.
+    pub fn json<D: DeserializeOwned>(&self) -> Result<D> {
+        Ok(serde_json::from_slice(&self.body)?)
+    }
+}
+
 /// An HTTP client to send requests to the registry.
 pub struct GenericHttpClient<'a, T: HttpClient> {
-    _inner: T,
+    inner: Arc<T>,
+    started_at: Instant,
     timeout: Duration,
     headers: HeaderMap<'a>,
+    retry_policy: RetryPolicy,
+    user_agent: Arc<str>,
+    max_body_size: u64,
 }
 
+/// Constructs a new `GenericHttpClient` whose requests must complete within the timeout,
+/// which is shared by all clones.
 pub(crate) fn new<'a, T: HttpClient>(client: T, timeout: Duration) -> GenericHttpClient<'a, T> {
     GenericHttpClient {
-        _inner: client,
+        inner: Arc::new(client),
+        started_at: Instant::now(),
         timeout,
         headers: HeaderMap::new(),
+        retry_policy: RetryPolicy::default(),
+        user_agent: default_user_agent().into(),
+        max_body_size: DEFAULT_MAX_BODY_SIZE,
     }
 }
 
@@ -47,11 +161,440 @@ impl<'a, T: HttpClient> GenericHttpClient<'a, T> {
         self
     }
 
-    pub fn get<D: DeserializeOwned>(self, url: &str) -> Result<D> {
-        T::get(url, self.timeout, self.headers)
+    /// Sets the policy to retry requests that fail with a transient error.
+    pub fn retry_policy(self, retry_policy: RetryPolicy) -> Self {
+        Self {
+            retry_policy,
+            ..self
+        }
+    }
+
+    /// Sets the User-Agent sent with every request, unless a request sets its own.
+    pub fn user_agent<U: AsRef<str>>(self, user_agent: U) -> Self {
+        Self {
+            user_agent: user_agent.as_ref().into(),
+            ..self
+        }
+    }
+
+    /// Sets the maximum size of response bodies in bytes.
+    pub fn max_body_size(self, max_body_size: u64) -> Self {
+        Self {
+            max_body_size,
+            ..self
+        }
+    }
+
+    /// Returns the time left before the deadline of the check.
+    pub fn remaining(&self) -> Duration {
+        self.timeout.saturating_sub(self.started_at.elapsed())
+    }
+
+    /// Returns a clone whose requests must complete within the timeout from now,
+    /// without exceeding the deadline of the check.
+    pub(crate) fn with_timeout(&self, timeout: Duration) -> Self {
+        Self {
+            started_at: Instant::now(),
+            timeout: timeout.min(self.remaining()),
+            ..self.clone()
+        }
     }
+
+    /// Sends a GET request and returns the response, whatever its status.
+    ///
+    /// The request gets the time left before the deadline, which is shared by all requests
+    /// of a check. Requests failing with [`HttpError::Connect`] or a 5xx or 429 status are
+    /// retried according to the retry policy, as long as the deadline is not exceeded.
+    /// Other errors, e.g. of TLS, an invalid URL or a body exceeding the maximum size,
+    /// are returned at once.
+    pub fn get_response(&self, url: &str) -> Result<Response> {
+        let mut retry = 0;
+
+        loop {
+            let timeout = self.remaining();
+            if timeout.is_zero() {
+                return Err(HttpError::DeadlineExceeded.into());
+            }
+
+            let mut headers = self.headers.clone();
+            if headers.get("User-Agent").is_none() {
+                headers.add("User-Agent", &self.user_agent);
+            }
+
+            let request = Request {
+                max_body_size: self.max_body_size,
+                ..Request::new(url, timeout, headers)
+            };
+            let result = self.inner.send(request).and_then(|resp| {
+                // In case the HTTP client does not limit the size itself
+                if resp.body.len() as u64 > self.max_body_size {
+                    return Err(HttpError::BodyTooLarge(self.max_body_size).into());
+                }
+
+                Ok(resp)
+            });
+
+            let transient = match &result {
+                Ok(resp) => resp.status >= 500 || resp.status == 429,
+                Err(e) => matches!(e.downcast_ref(), Some(HttpError::Connect(_))),
+            };
+
+            retry += 1;
+            if !transient || retry >= self.retry_policy.max_attempts() {
+                return result;
+            }
+
+            let delay = self.retry_policy.delay(retry);
+            if delay >= self.remaining() {
+                return result;
+            }
+
+            thread::sleep(delay);
+        }
+    }
+
+    /// Sends a GET request and deserializes the JSON response.
+    pub fn get<D: DeserializeOwned>(&self, url: &str) -> Result<D> {
+        self.get_success(url)?.json()
+    }
+
+    /// Sends a GET request and returns the response body as text.
+    pub fn get_text(&self, url: &str) -> Result<String> {
+        self.get_success(url)?.text()
+    }
+
+    fn get_success(&self, url: &str) -> Result<Response> {
+        let resp = self.get_response(url)?;
+        if !resp.is_success() {
+            return Err(HttpError::Status(resp.status).into());
+        }
+
+        // Captive portals and proxy login pages answer with HTML instead of the registry
+        if resp.is_html() {
+            return Err(HttpError::Html.into());
+        }
+
+        Ok(resp)
+    }
+}
+
+impl<T: HttpClient> Clone for GenericHttpClient<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+            started_at: self.started_at,
+            timeout: self.timeout,
+            headers: self.headers.clone(),
+            retry_policy: self.retry_policy,
+            user_agent: Arc::clone(&self.user_agent),
+            max_body_size: self.max_body_size,
+        }
+    }
+}
+
+/// Returns the User-Agent identifying `update-informer`, e.g. `update-informer/1.3.0`.
+pub(crate) fn default_user_agent() -> String {
+    format!("update-informer/{}", env!("CARGO_PKG_VERSION"))
+}
+
+/// Returns the User-Agent identifying the application and `update-informer`,
+/// e.g. `my-app/0.1.0 update-informer/1.3.0`.
+pub(crate) fn app_user_agent(name: &str, version: &str) -> String {
+    let app = name.rsplit('/').next().unwrap_or(name);
+
+    format!("{app}/{version} {}", default_user_agent())
+}
+
+/// Reads the PEM file with extra root certificates set by the `UPDATE_INFORMER_CA_FILE`
+/// environment variable.
+#[cfg(any(feature = "ureq", feature = "reqwest"))]
+fn env_root_certificates() -> Result<Option<Vec<u8>>> {
+    let Some(path) = std::env::var_os("UPDATE_INFORMER_CA_FILE") else {
+        return Ok(None);
+    };
+
+    let pem = std::fs::read(&path).map_err(|e| {
+        format!(
+            "unable to read {}: {e}",
+            std::path::Path::new(&path).display()
+        )
+    })?;
+    Ok(Some(pem))
+}
+
+/// Returns `true` for I/O errors of a connection that may succeed if retried,
+/// e.g. a refused or reset connection, found in the error or its sources.
+#[cfg(any(feature = "ureq", feature = "reqwest"))]
+fn is_connection_error(error: &(dyn std::error::Error + 'static)) -> bool {
+    use std::io::{self, ErrorKind};
+
+    let mut source = Some(error);
+    while let Some(error) = source {
+        if let Some(error) = error.downcast_ref::<io::Error>() {
+            if matches!(
+                error.kind(),
+                ErrorKind::ConnectionRefused
+                    | ErrorKind::ConnectionReset
+                    | ErrorKind::ConnectionAborted
+                    | ErrorKind::NotConnected
+                    | ErrorKind::BrokenPipe
+                    | ErrorKind::TimedOut
+                    | ErrorKind::UnexpectedEof
+            ) {
+                return true;
+            }
+        }
+
+        source = error.source();
+    }
+
+    false
 }
 
+/// An HTTP client to send requests to the registry.
+///
+/// The trait is object safe, so a configured client can be shared, e.g. as `Box<dyn HttpClient>`.
 pub trait HttpClient {
-    fn get<T: DeserializeOwned>(url: &str, timeout: Duration, headers: HeaderMap) -> Result<T>;
+    /// Sends a request and returns the response. Responses with an unsuccessful status,
+    /// e.g. 404, must be returned as responses rather than errors.
+    ///
+    /// Errors that may not happen again, e.g. a refused connection, a reset or a timeout,
+    /// should be returned as [`HttpError::Connect`], so the request is retried.
+    fn send(&self, request: Request) -> Result<Response>;
+}
+
+impl<C: HttpClient + ?Sized> HttpClient for &C {
+    fn send(&self, request: Request) -> Result<Response> {
+        (**self).send(request)
+    }
+}
+
+impl<C: HttpClient + ?Sized> HttpClient for Box<C> {
+    fn send(&self, request: Request) -> Result<Response> {
+        (**self).send(request)
+    }
+}
+
+impl<C: HttpClient + ?Sized> HttpClient for Arc<C> {
+    fn send(&self, request: Request) -> Result<Response> {
+        (**self).send(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A client returning the same response to every request.
+    struct Stub(Response);
+
+    impl HttpClient for Stub {
+        fn send(&self, _request: Request) -> Result<Response> {
+            Ok(self.0.clone())
+        }
+    }
+
+    fn client(response: Response) -> GenericHttpClient<'static, Box<dyn HttpClient>> {
+        new(Box::new(Stub(response)), Duration::from_secs(5))
+    }
+
+    #[test]
+    fn get_json_test() {
+        let resp = Response::new(200, r#"{"version":"1.0.0"}"#)
+            .with_header("Content-Type", "application/json");
+        let client = client(resp);
+
+        let json: serde_json::Value = client.get("https://example.com").expect("get json");
+        assert_eq!(json["version"], "1.0.0");
+
+        let resp = client
+            .get_response("https://example.com")
+            .expect("get response");
+        assert_eq!(resp.header("content-type"), Some("application/json"));
+    }
+
+    /// A client returning the given responses in order, then the last one.
+    struct Sequence(std::sync::Mutex<Vec<Response>>);
+
+    impl HttpClient for Sequence {
+        fn send(&self, _request: Request) -> Result<Response> {
+            let mut responses = self.0.lock().unwrap();
+            if responses.len() > 1 {
+                return Ok(responses.remove(0));
+            }
+
+            Ok(responses[0].clone())
+        }
+    }
+
+    fn sequence(statuses: &[u16]) -> GenericHttpClient<'static, Sequence> {
+        let responses = statuses.iter().map(|s| Response::new(*s, "{}")).collect();
+        let policy = RetryPolicy::new(3).backoff(Duration::ZERO, Duration::ZERO);
+
+        new(
+            Sequence(std::sync::Mutex::new(responses)),
+            Duration::from_secs(5),
+        )
+        .retry_policy(policy)
+    }
+
+    #[test]
+    fn retry_test() {
+        let resp = sequence(&[502, 429, 200]).get_response("https://example.com");
+        assert_eq!(resp.expect("get response").status, 200);
+
+        let resp = sequence(&[503, 503, 503, 200]).get_response("https://example.com");
+        assert_eq!(resp.expect("get response").status, 503);
+
+        let resp = sequence(&[404, 200]).get_response("https://example.com");
+        assert_eq!(resp.expect("get response").status, 404);
+
+        let client = sequence(&[502, 200]).retry_policy(RetryPolicy::none());
+        let resp = client.get_response("https://example.com");
+        assert_eq!(resp.expect("get response").status, 502);
+    }
+
+    /// A client failing every request with the error, which counts the requests.
+    struct Failing(Option<HttpError>, std::sync::atomic::AtomicUsize);
+
+    impl HttpClient for Failing {
+        fn send(&self, _request: Request) -> Result<Response> {
+            self.1.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+
+            match &self.0 {
+                Some(error) => Err(error.clone().into()),
+                None => Err("invalid peer certificate".into()),
+            }
+        }
+    }
+
+    fn failing(error: Option<HttpError>) -> usize {
+        let failing = Arc::new(Failing(error, Default::default()));
+        let policy = RetryPolicy::new(3).backoff(Duration::ZERO, Duration::ZERO);
+        let client = new(Arc::clone(&failing), Duration::from_secs(5)).retry_policy(policy);
+
+        assert!(client.get_response("https://example.com").is_err());
+        failing.1.load(std::sync::atomic::Ordering::SeqCst)
+    }
+
+    #[test]
+    fn retry_error_test() {
+        let error = HttpError::Connect("connection reset".to_string());
+        assert_eq!(failing(Some(error)), 3);
+        assert_eq!(failing(None), 1);
+    }
+
+    /// A client answering after a delay, which records the timeouts of requests.
+    #[derive(Default)]
+    struct Slow(std::sync::Mutex<Vec<Duration>>);
+
+    impl HttpClient for Slow {
+        fn send(&self, request: Request) -> Result<Response> {
+            self.0.lock().unwrap().push(request.timeout);
+            thread::sleep(Duration::from_millis(150));
+
+            Ok(Response::new(200, "{}"))
+        }
+    }
+
+    #[test]
+    fn deadline_test() {
+        let slow = Arc::new(Slow::default());
+        let client = new(Arc::clone(&slow), Duration::from_millis(400));
+
+        for _ in 0..3 {
+            assert!(client.clone().get_response("https://example.com").is_ok());
+        }
+
+        let error = client.get_response("https://example.com").unwrap_err();
+        assert_eq!(error.to_string(), "deadline exceeded");
+
+        let timeouts = slow.0.lock().unwrap();
+        assert_eq!(timeouts.len(), 3);
+        assert!(timeouts.windows(2).all(|pair| pair[1] < pair[0]));
+    }
+
+    /// A client recording the User-Agent of requests.
+    #[derive(Default)]
+    struct Recorder(std::sync::Mutex<Vec<String>>);
+
+    impl HttpClient for Recorder {
+        fn send(&self, request: Request) -> Result<Response> {
+            let user_agent = request.headers.get("user-agent").unwrap_or_default();
+            self.0.lock().unwrap().push(user_agent.to_string());
+
+            Ok(Response::new(200, "{}"))
+        }
+    }
+
+    #[test]
+    fn user_agent_test() {
+        let recorder = Arc::new(Recorder::default());
+        let client = new(Arc::clone(&recorder), Duration::from_secs(5));
+
+        client.get_response("https://example.com").unwrap();
+        client
+            .clone()
+            .user_agent(app_user_agent("github.com/owner/tool", "0.1.0"))
+            .get_response("https://example.com")
+            .unwrap();
+        client
+            .add_header("User-Agent", "custom")
+            .get_response("https://example.com")
+            .unwrap();
+
+        let version = env!("CARGO_PKG_VERSION");
+        let user_agents = recorder.0.lock().unwrap();
+        assert_eq!(
+            *user_agents,
+            [
+                format!("update-informer/{version}"),
+                format!("tool/0.1.0 update-informer/{version}"),
+                "custom".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn max_body_size_test() {
+        let client = client(Response::new(200, "x".repeat(100))).max_body_size(10);
+        let error = client.get_text("https://example.com").unwrap_err();
+
+        assert_eq!(
+            error.downcast_ref::<HttpError>(),
+            Some(&HttpError::BodyTooLarge(10))
+        );
+    }
+
+    #[test]
+    fn html_test() {
+        let portal = "\n<!DOCTYPE html>\n<html><body>Sign in to the Wi-Fi</body></html>";
+        let responses = [
+            Response::new(200, portal).with_header("Content-Type", "application/json"),
+            Response::new(200, "{}").with_header("Content-Type", "text/html; charset=utf-8"),
+        ];
+
+        for resp in responses {
+            let error = client(resp)
+                .get::<serde_json::Value>("https://example.com")
+                .unwrap_err();
+            assert_eq!(error.downcast_ref::<HttpError>(), Some(&HttpError::Html));
+        }
+
+        let resp = Response::new(200, "<?xml version=\"1.0\"?><metadata/>");
+        assert!(client(resp).get_text("https://example.com").is_ok());
+    }
+
+    #[test]
+    fn unsuccessful_status_test() {
+        let client = client(Response::new(404, "Not Found"));
+
+        let resp = client
+            .get_response("https://example.com")
+            .expect("get response");
+        assert_eq!(resp.status, 404);
+
+        let error = client.get_text("https://example.com").unwrap_err();
+        assert_eq!(error.downcast_ref(), Some(&HttpError::Status(404)));
+    }
 }
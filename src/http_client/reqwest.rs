@@ -1,25 +1,183 @@
 use crate::{
-    http_client::{HeaderMap, HttpClient},
-    Result,
+    http_client::{
+        env_root_certificates, is_connection_error, HttpClient, HttpError, Request, Response,
+    },
+    Error, Result,
 };
-use serde::de::DeserializeOwned;
-use std::time::Duration;
+use reqwest::{blocking::Client, Certificate};
+use std::{fs, io::Read, path::Path, sync::OnceLock};
 
-pub struct ReqwestHttpClient;
+/// An HTTP client based on `reqwest`.
+///
+/// By default, all requests share one client, which uses the proxy set by the `HTTPS_PROXY`,
+/// `HTTP_PROXY` or `ALL_PROXY` environment variables except for hosts in `NO_PROXY`, and
+/// trusts the root certificates from the PEM file set by `UPDATE_INFORMER_CA_FILE`.
+/// Use [`ReqwestHttpClient::from_client`] to send requests with a client configured by the application.
+#[derive(Clone, Debug, Default)]
+pub struct ReqwestHttpClient {
+    client: Option<Client>,
+}
+
+impl ReqwestHttpClient {
+    /// Constructs a new `ReqwestHttpClient` using the given blocking client.
+    ///
+    /// The client is used as-is, including its User-Agent, except for the timeout of the informer.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use update_informer::{http_client::ReqwestHttpClient, registry, Check};
+    ///
+    /// let client = reqwest::blocking::Client::builder()
+    ///     .user_agent("my-app/1.0")
+    ///     .build()
+    ///     .expect("build client");
+    ///
+    /// let informer = update_informer::new(registry::Crates, "crate_name", "0.1.0")
+    ///     .http_client(ReqwestHttpClient::from_client(client));
+    /// let _ = informer.check_version();
+    /// ```
+    pub fn from_client(client: Client) -> Self {
+        Self {
+            client: Some(client),
+        }
+    }
+
+    /// Constructs a new `ReqwestHttpClient` that trusts the root certificates from a PEM file
+    /// in addition to the built-in ones, e.g. the certificate of a corporate proxy.
+    pub fn with_ca_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let pem = fs::read(path)?;
+
+        Ok(Self::from_client(build_client(Some(&pem))?))
+    }
+}
 
 impl HttpClient for ReqwestHttpClient {
-    fn get<T: DeserializeOwned>(url: &str, timeout: Duration, headers: HeaderMap) -> Result<T> {
-        let mut req = reqwest::blocking::Client::builder()
-            .timeout(timeout)
-            .build()?
-            .get(url);
+    fn send(&self, request: Request) -> Result<Response> {
+        let client = match &self.client {
+            Some(client) => client,
+            None => shared_client()?,
+        };
+        let mut req = client.get(request.url).timeout(request.timeout);
+
+        for (key, value) in request.headers {
+            // The User-Agent of a client configured by the application takes precedence
+            if self.client.is_some() && key.eq_ignore_ascii_case("User-Agent") {
+                continue;
+            }
 
-        for (key, value) in headers {
             req = req.header(key, value);
         }
 
-        let json = req.send()?.json()?;
+        let resp = req.send().map_err(into_error)?;
+        let max = request.max_body_size;
+        if resp.content_length().is_some_and(|length| length > max) {
+            return Err(HttpError::BodyTooLarge(max).into());
+        }
+
+        let status = resp.status().as_u16();
+        let headers = resp
+            .headers()
+            .iter()
+            .filter_map(|(key, value)| Some((key.to_string(), value.to_str().ok()?.to_string())))
+            .collect();
+
+        // Read one more byte than allowed to detect larger bodies without a `Content-Length`
+        let mut body = Vec::new();
+        resp.take(max.saturating_add(1))
+            .read_to_end(&mut body)
+            .map_err(into_error)?;
+        if body.len() as u64 > max {
+            return Err(HttpError::BodyTooLarge(max).into());
+        }
+
+        Ok(Response {
+            status,
+            headers,
+            body,
+        })
+    }
+}
+
+/// Converts errors of the connection, e.g. a refused connection or a timeout,
+/// into [`HttpError::Connect`] so the request is retried.
+fn into_error<E: std::error::Error + 'static>(error: E) -> Error {
+    // Errors reading the body are I/O errors wrapping the error of `reqwest`
+    let mut transient = is_connection_error(&error);
+    let mut source: Option<&(dyn std::error::Error + 'static)> = Some(&error);
+    while let Some(e) = source {
+        if let Some(e) = e.downcast_ref::<reqwest::Error>() {
+            transient |= e.is_connect() || e.is_timeout();
+        }
+
+        source = e.source();
+    }
+
+    if transient {
+        return HttpError::Connect(error.to_string()).into();
+    }
+
+    Box::new(error)
+}
+
+/// Returns the client shared by all requests, so connections are reused.
+fn shared_client() -> Result<&'static Client> {
+    static CLIENT: OnceLock<Client> = OnceLock::new();
+
+    if let Some(client) = CLIENT.get() {
+        return Ok(client);
+    }
+
+    let client = build_client(env_root_certificates()?.as_deref())?;
+    Ok(CLIENT.get_or_init(|| client))
+}
+
+/// Builds a client, which reads the proxy from the environment variables by default.
+fn build_client(root_certificates: Option<&[u8]>) -> Result<Client> {
+    let mut builder = Client::builder();
+
+    if let Some(pem) = root_certificates {
+        for cert in Certificate::from_pem_bundle(pem)? {
+            builder = builder.add_root_certificate(cert);
+        }
+    }
+
+    Ok(builder.build()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http_client::{self, GenericHttpClient};
+    use mockito::mock;
+    use std::time::Duration;
+
+    #[test]
+    fn max_body_size_test() {
+        let _mock = mock("GET", "/reqwest/large")
+            .with_status(200)
+            .with_body("x".repeat(1024))
+            .create();
+
+        let client: GenericHttpClient<_> =
+            http_client::new(ReqwestHttpClient::default(), Duration::from_secs(5))
+                .max_body_size(100);
+
+        let url = format!("{}/reqwest/large", mockito::server_url());
+        let error = client.get_response(&url).unwrap_err();
+
+        assert_eq!(
+            error.downcast_ref::<HttpError>(),
+            Some(&HttpError::BodyTooLarge(100))
+        );
+    }
+
+    #[test]
+    fn with_ca_file_test() {
+        let client = ReqwestHttpClient::with_ca_file("tests/fixtures/http_client/ca.pem");
+        assert!(client.is_ok());
 
-        Ok(json)
+        let client = ReqwestHttpClient::with_ca_file("tests/fixtures/http_client/missing.pem");
+        assert!(client.is_err());
     }
 }
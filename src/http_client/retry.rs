@@ -0,0 +1,106 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    time::Duration,
+};
+
+/// A policy to retry requests that fail with a transient error: a connection error,
+/// a server error (5xx) or rate limiting (429).
+///
+/// Delays between attempts grow exponentially with random jitter, and retries stop when
+/// the timeout of the check would be exceeded, so the total time stays predictable.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::time::Duration;
+/// use update_informer::{http_client::RetryPolicy, registry, Check};
+///
+/// let policy = RetryPolicy::new(5).backoff(Duration::from_millis(100), Duration::from_secs(1));
+///
+/// let informer = update_informer::new(registry::Crates, "crate_name", "0.1.0").retry_policy(policy);
+/// let _ = informer.check_version();
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+}
+
+impl RetryPolicy {
+    /// Constructs a new `RetryPolicy` sending a request at most `max_attempts` times.
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            base_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(2),
+        }
+    }
+
+    /// Constructs a new `RetryPolicy` that does not retry requests.
+    pub fn none() -> Self {
+        Self::new(1)
+    }
+
+    /// Sets the delay before the first retry, which doubles for each retry up to `max_delay`.
+    /// By default, it is 200 milliseconds up to 2 seconds.
+    pub fn backoff(self, base_delay: Duration, max_delay: Duration) -> Self {
+        Self {
+            base_delay,
+            max_delay,
+            ..self
+        }
+    }
+
+    /// Returns the maximum number of attempts.
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Returns a random delay before the given retry, starting from 1.
+    pub(crate) fn delay(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        let delay = self.base_delay.saturating_mul(factor).min(self.max_delay);
+
+        // Full jitter, so that many clients do not retry at the same time
+        delay.mul_f64(random())
+    }
+}
+
+impl Default for RetryPolicy {
+    /// Sends a request at most 3 times.
+    fn default() -> Self {
+        Self::new(3)
+    }
+}
+
+/// Returns a random number between 0 and 1 without an extra dependency.
+fn random() -> f64 {
+    let value = RandomState::new().build_hasher().finish();
+
+    value as f64 / u64::MAX as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delay_test() {
+        let policy =
+            RetryPolicy::new(5).backoff(Duration::from_millis(100), Duration::from_millis(300));
+
+        for retry in 1..5 {
+            let max = Duration::from_millis([100, 200, 300, 300][retry as usize - 1]);
+            assert!(policy.delay(retry) <= max, "retry {retry}");
+        }
+    }
+
+    #[test]
+    fn max_attempts_test() {
+        assert_eq!(RetryPolicy::none().max_attempts(), 1);
+        assert_eq!(RetryPolicy::new(0).max_attempts(), 1);
+        assert_eq!(RetryPolicy::default().max_attempts(), 3);
+    }
+}
@@ -1,14 +1,13 @@
 use crate::{
-    http_client::{HeaderMap, HttpClient},
+    http_client::{HttpClient, Request, Response},
     Result,
 };
-use serde::de::DeserializeOwned;
-use std::time::Duration;
 
+#[derive(Clone, Debug, Default)]
 pub struct UndefinedHttpClient;
 
 impl HttpClient for UndefinedHttpClient {
-    fn get<T: DeserializeOwned>(_url: &str, _timeout: Duration, _headers: HeaderMap) -> Result<T> {
+    fn send(&self, _request: Request) -> Result<Response> {
         panic!("choose HTTP client (ureq or reqwest) or implement your own");
     }
 }
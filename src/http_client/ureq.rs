@@ -1,33 +1,227 @@
 use crate::{
-    http_client::{HeaderMap, HttpClient},
-    Result,
+    http_client::{
+        env_root_certificates, is_connection_error, HttpClient, HttpError, Request, Response,
+    },
+    Error, Result,
 };
-use serde::de::DeserializeOwned;
-use std::time::Duration;
-use ureq::Agent;
+use std::{fs, path::Path, sync::OnceLock};
+use ureq::{
+    tls::{parse_pem, Certificate, PemItem, RootCerts, TlsConfig},
+    Agent, Proxy,
+};
+
+/// An HTTP client based on `ureq`.
+///
+/// By default, all requests share one agent, which uses the proxy set by the `HTTPS_PROXY`,
+/// `HTTP_PROXY` or `ALL_PROXY` environment variables except for hosts in `NO_PROXY`, and
+/// trusts the root certificates from the PEM file set by `UPDATE_INFORMER_CA_FILE`.
+/// Use [`UreqHttpClient::from_agent`] to send requests with an agent configured by the application.
+#[derive(Clone, Debug, Default)]
+pub struct UreqHttpClient {
+    agent: Option<Agent>,
+}
+
+impl UreqHttpClient {
+    /// Constructs a new `UreqHttpClient` using the given agent.
+    ///
+    /// The agent is used as-is, including its User-Agent, except for the timeout of the
+    /// informer and unsuccessful statuses, which are returned as responses rather than errors.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use update_informer::{http_client::UreqHttpClient, registry, Check};
+    ///
+    /// let agent: ureq::Agent = ureq::Agent::config_builder()
+    ///     .user_agent("my-app/1.0")
+    ///     .build()
+    ///     .into();
+    ///
+    /// let informer = update_informer::new(registry::Crates, "crate_name", "0.1.0")
+    ///     .http_client(UreqHttpClient::from_agent(agent));
+    /// let _ = informer.check_version();
+    /// ```
+    pub fn from_agent(agent: Agent) -> Self {
+        Self { agent: Some(agent) }
+    }
+
+    /// Constructs a new `UreqHttpClient` that trusts the root certificates from a PEM file
+    /// in addition to the bundled ones, e.g. the certificate of a corporate proxy.
+    pub fn with_ca_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let pem = fs::read(path)?;
 
-pub struct UreqHttpClient;
+        Ok(Self::from_agent(build_agent(Some(&pem))?))
+    }
+}
 
 impl HttpClient for UreqHttpClient {
-    fn get<T: DeserializeOwned>(url: &str, timeout: Duration, headers: HeaderMap) -> Result<T> {
-        let config = Agent::config_builder().timeout_global(Some(timeout));
-
-        #[cfg(all(feature = "native-tls", not(feature = "rustls-tls")))]
-        let config = config.tls_config(
-            ureq::tls::TlsConfig::builder()
-                .provider(ureq::tls::TlsProvider::NativeTls)
-                .build(),
-        );
+    fn send(&self, request: Request) -> Result<Response> {
+        let agent = match &self.agent {
+            Some(agent) => agent,
+            None => shared_agent()?,
+        };
+        let mut req = agent
+            .get(request.url)
+            .config()
+            .timeout_global(Some(request.timeout))
+            .http_status_as_error(false)
+            .build();
 
-        let agent: Agent = config.build().into();
-        let mut req = agent.get(url);
+        for (header, value) in request.headers {
+            // The User-Agent of an agent configured by the application takes precedence
+            if self.agent.is_some() && header.eq_ignore_ascii_case("User-Agent") {
+                continue;
+            }
 
-        for (header, value) in headers {
             req = req.header(header, value);
         }
 
-        let json = req.call()?.body_mut().read_json()?;
+        let mut resp = req.call().map_err(into_error)?;
+        let body = match resp
+            .body_mut()
+            .with_config()
+            .limit(request.max_body_size)
+            .read_to_vec()
+        {
+            Ok(body) => body,
+            Err(ureq::Error::BodyExceedsLimit(max)) => {
+                return Err(HttpError::BodyTooLarge(max).into())
+            }
+            Err(e) => return Err(into_error(e)),
+        };
+        let headers = resp
+            .headers()
+            .iter()
+            .filter_map(|(key, value)| Some((key.to_string(), value.to_str().ok()?.to_string())))
+            .collect();
+
+        Ok(Response {
+            status: resp.status().as_u16(),
+            headers,
+            body,
+        })
+    }
+}
+
+/// Converts errors of the connection, e.g. a refused connection or a timeout,
+/// into [`HttpError::Connect`] so the request is retried.
+fn into_error(error: ureq::Error) -> Error {
+    let transient = match &error {
+        ureq::Error::Io(e) => is_connection_error(e),
+        ureq::Error::Timeout(_)
+        | ureq::Error::HostNotFound
+        | ureq::Error::ConnectionFailed
+        | ureq::Error::BodyStalled => true,
+        _ => false,
+    };
+
+    if transient {
+        return HttpError::Connect(error.to_string()).into();
+    }
+
+    error.into()
+}
+
+/// Returns the agent shared by all requests, so connections are reused.
+fn shared_agent() -> Result<&'static Agent> {
+    static AGENT: OnceLock<Agent> = OnceLock::new();
+
+    if let Some(agent) = AGENT.get() {
+        return Ok(agent);
+    }
+
+    let agent = build_agent(env_root_certificates()?.as_deref())?;
+    Ok(AGENT.get_or_init(|| agent))
+}
+
+fn build_agent(root_certificates: Option<&[u8]>) -> Result<Agent> {
+    let tls = TlsConfig::builder();
+
+    #[cfg(all(feature = "native-tls", not(feature = "rustls-tls")))]
+    let tls = tls.provider(ureq::tls::TlsProvider::NativeTls);
+
+    let tls = match root_certificates {
+        Some(pem) => tls.root_certs(root_certs(pem)?),
+        None => tls,
+    };
+
+    let agent = Agent::config_builder()
+        .proxy(Proxy::try_from_env())
+        .tls_config(tls.build())
+        .build()
+        .into();
+
+    Ok(agent)
+}
+
+/// Returns the bundled root certificates together with the certificates from a PEM file.
+fn root_certs(pem: &[u8]) -> Result<RootCerts> {
+    let mut certs: Vec<Certificate<'static>> = webpki_root_certs::TLS_SERVER_ROOT_CERTS
+        .iter()
+        .map(|cert| Certificate::from_der(cert.as_ref()))
+        .collect();
+
+    for item in parse_pem(pem) {
+        if let PemItem::Certificate(cert) = item? {
+            certs.push(cert);
+        }
+    }
+
+    Ok(RootCerts::new_with_certs(&certs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http_client::{self, GenericHttpClient};
+    use mockito::mock;
+    use std::time::Duration;
+
+    #[test]
+    fn from_agent_test() {
+        let _mock = mock("GET", "/ureq/agent")
+            .match_header("user-agent", "my-app/1.0")
+            .with_status(404)
+            .create();
+
+        let agent: Agent = Agent::config_builder()
+            .user_agent("my-app/1.0")
+            .build()
+            .into();
+        let client: GenericHttpClient<_> =
+            http_client::new(UreqHttpClient::from_agent(agent), Duration::from_secs(5));
+
+        let url = format!("{}/ureq/agent", mockito::server_url());
+        let resp = client.get_response(&url).expect("get response");
+
+        assert_eq!(resp.status, 404);
+    }
+
+    #[test]
+    fn max_body_size_test() {
+        let _mock = mock("GET", "/ureq/large")
+            .with_status(200)
+            .with_body("x".repeat(1024))
+            .create();
+
+        let client: GenericHttpClient<_> =
+            http_client::new(UreqHttpClient::default(), Duration::from_secs(5)).max_body_size(100);
+
+        let url = format!("{}/ureq/large", mockito::server_url());
+        let error = client.get_response(&url).unwrap_err();
+
+        assert_eq!(
+            error.downcast_ref::<HttpError>(),
+            Some(&HttpError::BodyTooLarge(100))
+        );
+    }
+
+    #[test]
+    fn with_ca_file_test() {
+        let client = UreqHttpClient::with_ca_file("tests/fixtures/http_client/ca.pem");
+        assert!(client.is_ok());
 
-        Ok(json)
+        let client = UreqHttpClient::with_ca_file("tests/fixtures/http_client/missing.pem");
+        assert!(client.is_err());
     }
 }
@@ -1,19 +1,30 @@
 #![doc = include_str!("../README.md")]
 
 use crate::{
-    http_client::{DefaultHttpClient, HttpClient},
+    cache::{CacheStorage, Clock, FileStorage, SystemClock},
+    http_client::{DefaultHttpClient, HttpClient, HttpError, RetryPolicy},
     version_file::VersionFile,
 };
 use std::time::Duration;
 
+pub use batch::Batch;
+pub use offline::set_offline;
 pub use package::Package;
 pub use registry::Registry;
-pub use version::Version;
+pub use release::{Release, Update};
+pub use version::{Version, VersionScheme};
 
+mod batch;
+mod fake;
+mod offline;
 mod package;
+mod release;
 mod version;
 mod version_file;
 
+#[cfg(any(feature = "npm", feature = "pypi"))]
+mod base64;
+
 #[cfg(test)]
 mod test_helper;
 
@@ -23,6 +34,12 @@ pub mod registry;
 /// An HTTP client to send requests to the registry.
 pub mod http_client;
 
+/// A storage of cached versions and a clock to check once per interval.
+pub mod cache;
+
+/// Utilities to test applications without network access.
+pub mod testing;
+
 type Error = Box<dyn std::error::Error>;
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -34,6 +51,15 @@ pub trait Check {
     {
         Ok(None)
     }
+
+    /// Checks for a new version in the registry and returns it together with the release
+    /// details provided by the registry, e.g. a download URL.
+    fn check_update(self) -> Result<Option<Update>>
+    where
+        Self: Sized,
+    {
+        Ok(None)
+    }
 }
 
 /// Checks for a new version on Crates.io, GitHub, Npm or PyPi.
@@ -45,12 +71,17 @@ pub struct UpdateInformer<
     V: AsRef<str>,
     H: HttpClient = DefaultHttpClient,
 > {
-    _registry: R,
+    registry: R,
     name: N,
     version: V,
     http_client: H,
     interval: Duration,
     timeout: Duration,
+    retry_policy: RetryPolicy,
+    user_agent: Option<String>,
+    max_body_size: u64,
+    cache_storage: Box<dyn CacheStorage>,
+    clock: Box<dyn Clock>,
 }
 
 /// Constructs a new `UpdateInformer`.
@@ -77,12 +108,17 @@ where
     V: AsRef<str>,
 {
     UpdateInformer {
-        _registry: registry,
+        registry,
         name,
         version,
-        http_client: DefaultHttpClient {},
+        http_client: DefaultHttpClient::default(),
         interval: Duration::from_secs(60 * 60 * 24), // Once a day
         timeout: Duration::from_secs(5),
+        retry_policy: RetryPolicy::default(),
+        user_agent: None,
+        max_body_size: http_client::DEFAULT_MAX_BODY_SIZE,
+        cache_storage: Box::new(FileStorage::new()),
+        clock: Box::new(SystemClock),
     }
 }
 
@@ -114,11 +150,12 @@ where
         Self { interval, ..self }
     }
 
-    /// Sets a request timeout.
+    /// Sets a timeout for the whole check.
     ///
     /// # Arguments
     ///
-    /// * `timeout` - A request timeout. By default, it is 5 seconds.
+    /// * `timeout` - A deadline shared by all requests of a check, e.g. by a token request and
+    ///   a request for tags. Each request gets the time left. By default, it is 5 seconds.
     ///
     /// # Examples
     ///
@@ -135,6 +172,98 @@ where
         Self { timeout, ..self }
     }
 
+    /// Sets a policy to retry requests that fail with a transient error, e.g. 502.
+    ///
+    /// # Arguments
+    ///
+    /// * `retry_policy` - By default, a request is sent at most 3 times within the timeout.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use update_informer::{http_client::RetryPolicy, registry, Check};
+    ///
+    /// let informer = update_informer::new(registry::Crates, "crate_name", "0.1.0").retry_policy(RetryPolicy::none());
+    /// let _ = informer.check_version();
+    /// ```
+    pub fn retry_policy(self, retry_policy: RetryPolicy) -> Self {
+        Self {
+            retry_policy,
+            ..self
+        }
+    }
+
+    /// Sets the User-Agent sent to the registry.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_agent` - By default, it identifies the project and `update-informer`,
+    ///   e.g. `crate_name/0.1.0 update-informer/1.3.0`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use update_informer::{registry, Check};
+    ///
+    /// let informer = update_informer::new(registry::Crates, "crate_name", "0.1.0")
+    ///     .user_agent("my-app/0.1.0 (https://example.com)");
+    /// let _ = informer.check_version();
+    /// ```
+    pub fn user_agent<U: Into<String>>(self, user_agent: U) -> Self {
+        Self {
+            user_agent: Some(user_agent.into()),
+            ..self
+        }
+    }
+
+    /// Sets the maximum size of responses from the registry.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_body_size` - The maximum size of a response body in bytes. By default, it is 16 MiB.
+    pub fn max_body_size(self, max_body_size: u64) -> Self {
+        Self {
+            max_body_size,
+            ..self
+        }
+    }
+
+    /// Sets a storage of the cached version.
+    ///
+    /// # Arguments
+    ///
+    /// * `cache_storage` - A type that implements the `CacheStorage` trait. By default, it is
+    ///   a file in the cache directory.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use update_informer::{cache::FileStorage, registry, Check};
+    ///
+    /// let informer = update_informer::new(registry::Crates, "crate_name", "0.1.0")
+    ///     .cache_storage(FileStorage::with_dir("/var/cache/my-app"));
+    /// let _ = informer.check_version();
+    /// ```
+    pub fn cache_storage<S: CacheStorage + 'static>(self, cache_storage: S) -> Self {
+        Self {
+            cache_storage: Box::new(cache_storage),
+            ..self
+        }
+    }
+
+    /// Sets a clock to check whether the interval has expired.
+    ///
+    /// # Arguments
+    ///
+    /// * `clock` - A type that implements the `Clock` trait, e.g. [`testing::MockClock`]
+    ///   to test the interval. By default, it is the system clock.
+    pub fn clock<C: Clock + 'static>(self, clock: C) -> Self {
+        Self {
+            clock: Box::new(clock),
+            ..self
+        }
+    }
+
     /// Sets an HTTP client to send request to the registry.
     ///
     /// # Arguments
@@ -144,21 +273,20 @@ where
     /// # Examples
     ///
     /// ```rust
-    /// use isahc::ReadResponseExt;
-    /// use std::time::Duration;
-    /// use serde::de::DeserializeOwned;
-    /// use update_informer::{http_client::{HeaderMap, HttpClient}, registry, Check};
+    /// use isahc::{config::Configurable, ReadResponseExt};
+    /// use update_informer::{http_client::{HttpClient, Request, Response}, registry, Check};
     ///
     /// struct YourOwnHttpClient;
     ///
     /// impl HttpClient for YourOwnHttpClient {
-    ///     fn get<T: DeserializeOwned>(
-    ///         url: &str,
-    ///         _timeout: Duration,
-    ///         _headers: HeaderMap,
-    ///     ) -> update_informer::Result<T> {
-    ///         let json = isahc::get(url)?.json()?;
-    ///         Ok(json)
+    ///     fn send(&self, request: Request) -> update_informer::Result<Response> {
+    ///         let mut req = isahc::Request::get(request.url).timeout(request.timeout);
+    ///         for (key, value) in request.headers {
+    ///             req = req.header(key, value);
+    ///         }
+    ///
+    ///         let mut resp = isahc::send(req.body(())?)?;
+    ///         Ok(Response::new(resp.status().as_u16(), resp.bytes()?))
     ///     }
     /// }
     ///
@@ -167,11 +295,16 @@ where
     /// ```
     pub fn http_client<C: HttpClient>(self, http_client: C) -> UpdateInformer<R, N, V, C> {
         UpdateInformer {
-            _registry: self._registry,
+            registry: self.registry,
             name: self.name,
             version: self.version,
             interval: self.interval,
             timeout: self.timeout,
+            retry_policy: self.retry_policy,
+            user_agent: self.user_agent,
+            max_body_size: self.max_body_size,
+            cache_storage: self.cache_storage,
+            clock: self.clock,
             http_client,
         }
     }
@@ -186,7 +319,8 @@ where
 {
     /// Checks for a new version in the registry.
     ///
-    /// In case of a non-zero [`interval()`](Self::interval), this will create or access a cache file.
+    /// In case of a non-zero [`interval()`](Self::interval), this will create or access a cache file,
+    /// or the [`cache_storage()`](Self::cache_storage).
     ///
     /// # Examples
     ///
@@ -199,47 +333,114 @@ where
     /// let _ = informer.check_version();
     /// ```
     fn check_version(self) -> Result<Option<Version>> {
-        let pkg = Package::new(self.name.as_ref(), self.version.as_ref())?;
-        let client = http_client::new(self.http_client, self.timeout);
+        let update = self.check_update()?;
+
+        Ok(update.map(Version::from))
+    }
+
+    /// Checks for a new version in the registry and returns it together with the release details.
+    ///
+    /// In case of a non-zero [`interval()`](Self::interval), this will create or access a cache file,
+    /// or the [`cache_storage()`](Self::cache_storage).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use update_informer::{registry, Check};
+    ///
+    /// let informer = update_informer::new(registry::Crates, "crate_name", "0.1.0");
+    /// if let Ok(Some(update)) = informer.check_update() {
+    ///     println!("New version: {}", update.version());
+    ///
+    ///     if update.is_required() {
+    ///         println!("This version is no longer supported");
+    ///     }
+    /// }
+    /// ```
+    fn check_update(self) -> Result<Option<Update>> {
+        let scheme = self.registry.version_scheme();
+
+        // Integration tests of applications can force the outcome of a check
+        if let Some(outcome) = fake::Outcome::from_env()? {
+            fake::wait(fake::latency_from_env()?, self.timeout)?;
+            return outcome.check(self.version.as_ref(), scheme);
+        }
+
+        if offline::is_offline() {
+            return Ok(None);
+        }
+
+        let pkg = Package::with_scheme(self.name.as_ref(), self.version.as_ref(), scheme)?;
+        let user_agent = match self.user_agent {
+            Some(user_agent) => user_agent,
+            None => http_client::app_user_agent(self.name.as_ref(), self.version.as_ref()),
+        };
+        let client = http_client::new(self.http_client, self.timeout)
+            .retry_policy(self.retry_policy)
+            .user_agent(user_agent)
+            .max_body_size(self.max_body_size);
 
         // If the interval is zero, don't use the cache file
-        let latest_version = if self.interval.is_zero() {
-            match R::get_latest_version(client, &pkg)? {
-                Some(v) => v,
+        let latest_release = if self.interval.is_zero() {
+            match self.registry.get_latest_release(client, &pkg)? {
+                Some(r) => r,
                 None => return Ok(None),
             }
         } else {
-            let latest_version_file = VersionFile::new(R::NAME, &pkg, self.version.as_ref())?;
+            let latest_version_file = VersionFile::new(
+                &self.registry.cache_name(),
+                &pkg,
+                self.version.as_ref(),
+                self.cache_storage.as_ref(),
+                self.clock.as_ref(),
+            );
             let last_modified = latest_version_file.last_modified()?;
 
             if last_modified >= self.interval {
-                // This is needed to update mtime of the file
+                // Until a new release is found, the package is not checked again for an interval
                 latest_version_file.recreate_file()?;
 
-                match R::get_latest_version(client, &pkg)? {
-                    Some(v) => {
-                        latest_version_file.write_version(&v)?;
-                        v
+                match self.registry.get_latest_release(client, &pkg)? {
+                    Some(r) => {
+                        latest_version_file.write_release(&r)?;
+                        r
                     }
                     None => return Ok(None),
                 }
             } else {
-                latest_version_file.get_version()?
+                latest_version_file.get_release()?
             }
         };
 
-        let latest_version = Version::parse(latest_version)?;
-        if &latest_version > pkg.version() {
-            return Ok(Some(latest_version));
-        }
-
-        Ok(None)
+        Update::check(&pkg, latest_release, scheme)
     }
 }
 
+/// Constructs a new [`Batch`] to check many packages at once.
+///
+/// # Examples
+///
+/// ```rust
+/// use update_informer::registry;
+///
+/// let results = update_informer::batch()
+///     .add(registry::Crates, "plugin-a", "0.1.0")
+///     .add(registry::Crates, "plugin-b", "1.2.0")
+///     .check_versions();
+/// ```
+pub fn batch() -> Batch {
+    Batch::new(DefaultHttpClient::default())
+}
+
 /// Fake `UpdateInformer`. Used only for tests.
+///
+/// By default, it returns the desired version as a new version. Use [`no_update()`](Self::no_update),
+/// [`error()`](Self::error) and [`latency()`](Self::latency) to test other outcomes of a check.
 pub struct FakeUpdateInformer<V: AsRef<str>> {
     version: V,
+    outcome: fake::Outcome,
+    latency: Duration,
+    timeout: Duration,
 }
 
 /// Constructs a new `FakeUpdateInformer`.
@@ -267,15 +468,79 @@ where
 {
     FakeUpdateInformer {
         version: new_version,
+        outcome: fake::Outcome::Update(None),
+        latency: Duration::ZERO,
+        timeout: Duration::from_secs(5),
     }
 }
 
 impl<V: AsRef<str>> FakeUpdateInformer<V> {
+    /// Returns no new version, as if the project is up to date.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use update_informer::{registry, Check};
+    ///
+    /// let informer = update_informer::fake(registry::Crates, "crate_name", "0.1.0", "1.0.0").no_update();
+    /// assert_eq!(informer.check_version().unwrap(), None);
+    /// ```
+    pub fn no_update(self) -> Self {
+        Self {
+            outcome: fake::Outcome::NoUpdate,
+            ..self
+        }
+    }
+
+    /// Returns the error, as if the registry could not be checked.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use update_informer::{http_client::HttpError, registry, Check};
+    ///
+    /// let informer = update_informer::fake(registry::Crates, "crate_name", "0.1.0", "1.0.0")
+    ///     .error(HttpError::Status(503));
+    /// assert!(informer.check_version().is_err());
+    /// ```
+    pub fn error(self, error: HttpError) -> Self {
+        Self {
+            outcome: fake::Outcome::Error(error),
+            ..self
+        }
+    }
+
+    /// Waits before returning, as a slow registry would. If the latency exceeds the
+    /// [`timeout()`](Self::timeout), the check fails with [`HttpError::DeadlineExceeded`].
+    pub fn latency(self, latency: Duration) -> Self {
+        Self { latency, ..self }
+    }
+
     pub fn interval(self, _interval: Duration) -> Self {
         self
     }
 
-    pub fn timeout(self, _timeout: Duration) -> Self {
+    pub fn timeout(self, timeout: Duration) -> Self {
+        Self { timeout, ..self }
+    }
+
+    pub fn retry_policy(self, _retry_policy: RetryPolicy) -> Self {
+        self
+    }
+
+    pub fn user_agent<U: Into<String>>(self, _user_agent: U) -> Self {
+        self
+    }
+
+    pub fn max_body_size(self, _max_body_size: u64) -> Self {
+        self
+    }
+
+    pub fn cache_storage<S: CacheStorage + 'static>(self, _cache_storage: S) -> Self {
+        self
+    }
+
+    pub fn clock<C: Clock + 'static>(self, _clock: C) -> Self {
         self
     }
 
@@ -301,22 +566,43 @@ impl<V: AsRef<str>> Check for FakeUpdateInformer<V> {
     /// assert_eq!(version.unwrap().to_string(), "v1.0.0");
     /// ```
     fn check_version(self) -> Result<Option<Version>> {
-        let version = Version::parse(self.version.as_ref())?;
+        let update = self.check_update()?;
 
-        Ok(Some(version))
+        Ok(update.map(Version::from))
+    }
+
+    /// Returns the desired version as a new version without release details,
+    /// or the outcome set by [`no_update()`](Self::no_update) or [`error()`](Self::error).
+    fn check_update(self) -> Result<Option<Update>> {
+        fake::wait(self.latency, self.timeout)?;
+
+        let version = self.version.as_ref();
+        let outcome = match self.outcome {
+            fake::Outcome::Update(None) => fake::Outcome::Update(Some(version.to_string())),
+            outcome => outcome,
+        };
+
+        outcome.check(version, VersionScheme::Semver)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{registry::Crates, test_helper::within_test_dir};
+    use crate::{
+        cache::{CacheEntry, MemoryStorage},
+        registry::Crates,
+        test_helper::{read_globals, write_globals},
+        testing::MockClock,
+    };
     use mockito::Mock;
-    use std::fs;
+    use std::time::SystemTime;
 
     const PKG_NAME: &str = "repo";
     const CURRENT_VERSION: &str = "3.1.0";
     const LATEST_VERSION: &str = "3.1.1";
+    const CACHE_KEY: &str = "crates-repo";
+    const DAY: Duration = Duration::from_secs(60 * 60 * 24);
 
     fn mock_crates(pkg: &str) -> Mock {
         let pkg = Package::new(pkg, CURRENT_VERSION).unwrap();
@@ -329,117 +615,179 @@ mod tests {
         mock
     }
 
+    fn cached(storage: &MemoryStorage) -> Option<String> {
+        let entry = storage.read(CACHE_KEY).expect("read cache");
+        entry.map(|entry| entry.contents)
+    }
+
     #[test]
     fn no_new_version_with_interval_test() {
-        within_test_dir(|_| {
-            let informer = new(Crates, PKG_NAME, CURRENT_VERSION);
-            let result = informer.check_version();
+        let _globals = read_globals();
+        let informer = new(Crates, PKG_NAME, CURRENT_VERSION).cache_storage(MemoryStorage::new());
+        let result = informer.check_version();
 
-            assert!(result.is_ok());
-            assert_eq!(result.unwrap(), None);
-        });
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), None);
     }
 
     #[test]
     fn no_new_version_on_registry_test() {
-        within_test_dir(|_| {
-            let _mock = mock_crates(PKG_NAME);
-            let informer = new(Crates, PKG_NAME, LATEST_VERSION).interval(Duration::ZERO);
-            let result = informer.check_version();
+        let _globals = read_globals();
+        let _mock = mock_crates(PKG_NAME);
+        let informer = new(Crates, PKG_NAME, LATEST_VERSION).interval(Duration::ZERO);
+        let result = informer.check_version();
 
-            assert!(result.is_ok());
-            assert_eq!(result.unwrap(), None);
-        });
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), None);
     }
 
     #[test]
     fn check_version_on_crates_test() {
-        within_test_dir(|_| {
-            let _mock = mock_crates(PKG_NAME);
-            let informer = new(Crates, PKG_NAME, CURRENT_VERSION).interval(Duration::ZERO);
-            let result = informer.check_version();
-            let version = Version::parse(LATEST_VERSION).expect("parse version");
+        let _globals = read_globals();
+        let _mock = mock_crates(PKG_NAME);
+        let informer = new(Crates, PKG_NAME, CURRENT_VERSION).interval(Duration::ZERO);
+        let result = informer.check_version();
+        let version = Version::parse(LATEST_VERSION).expect("parse version");
 
-            assert!(result.is_ok());
-            assert_eq!(result.unwrap(), Some(version));
-        });
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), Some(version));
     }
 
     #[test]
-    fn return_version_from_file_test() {
-        within_test_dir(|version_file| {
-            fs::write(version_file, "4.0.0").expect("create file");
+    fn check_update_on_crates_test() {
+        let _globals = read_globals();
+        let _mock = mock_crates(PKG_NAME);
+        let informer = new(Crates, PKG_NAME, CURRENT_VERSION).interval(Duration::ZERO);
+        let update = informer.check_update().expect("check update");
+        let version = Version::parse(LATEST_VERSION).expect("parse version");
+
+        let update = update.expect("get update");
+        assert_eq!(update.version(), &version);
+        assert_eq!(update.release(), &Release::new(LATEST_VERSION));
+        assert!(!update.is_required());
+    }
 
-            let informer = new(Crates, PKG_NAME, CURRENT_VERSION);
-            let result = informer.check_version();
-            let version = Version::parse("4.0.0").expect("parse version");
+    #[test]
+    fn offline_test() {
+        let _globals = write_globals();
+        let mock = mock_crates(PKG_NAME).expect(0);
+        let storage = MemoryStorage::new();
 
-            assert!(result.is_ok());
-            assert_eq!(result.unwrap(), Some(version));
-        });
+        set_offline(true);
+        let informer = new(Crates, PKG_NAME, CURRENT_VERSION).cache_storage(storage.clone());
+        let result = informer.check_version();
+        set_offline(false);
+
+        assert_eq!(result.unwrap(), None);
+        assert_eq!(cached(&storage), None);
+        mock.assert();
+    }
+
+    #[test]
+    fn return_version_from_file_test() {
+        let _globals = read_globals();
+        let storage = MemoryStorage::new();
+        let clock = MockClock::new(SystemTime::UNIX_EPOCH);
+        let entry = CacheEntry::new("4.0.0", clock.now());
+        storage.write(CACHE_KEY, entry).expect("write cache");
+
+        let informer = new(Crates, PKG_NAME, CURRENT_VERSION)
+            .cache_storage(storage)
+            .clock(clock);
+        let result = informer.check_version();
+        let version = Version::parse("4.0.0").expect("parse version");
+
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), Some(version));
     }
 
     #[test]
     fn create_version_file_test() {
-        within_test_dir(|version_file| {
-            assert!(!version_file.exists());
+        let _globals = read_globals();
+        let storage = MemoryStorage::new();
 
-            let informer = new(Crates, PKG_NAME, CURRENT_VERSION);
-            let result = informer.check_version();
-            assert!(result.is_ok());
-            assert!(version_file.exists());
+        let informer = new(Crates, PKG_NAME, CURRENT_VERSION).cache_storage(storage.clone());
+        let result = informer.check_version();
 
-            let version = fs::read_to_string(version_file).expect("read file");
-            assert_eq!(version, CURRENT_VERSION);
-        });
+        assert!(result.is_ok());
+        assert_eq!(cached(&storage), Some(CURRENT_VERSION.to_string()));
     }
 
     #[test]
     fn do_not_create_version_file_test() {
-        within_test_dir(|version_file| {
-            assert!(!version_file.exists());
+        let _globals = read_globals();
+        let _mock = mock_crates(PKG_NAME);
+        let storage = MemoryStorage::new();
 
-            let _mock = mock_crates(PKG_NAME);
-            let informer = new(Crates, PKG_NAME, CURRENT_VERSION).interval(Duration::ZERO);
-            let result = informer.check_version();
+        let informer = new(Crates, PKG_NAME, CURRENT_VERSION)
+            .interval(Duration::ZERO)
+            .cache_storage(storage.clone());
+        let result = informer.check_version();
 
-            assert!(result.is_ok());
-            assert!(!version_file.exists());
-        });
+        assert!(result.is_ok());
+        assert_eq!(cached(&storage), None);
+    }
+
+    #[test]
+    fn interval_expiry_test() {
+        let _globals = read_globals();
+        let mock = mock_crates(PKG_NAME).expect(1);
+        let storage = MemoryStorage::new();
+        let clock = MockClock::new(SystemTime::UNIX_EPOCH);
+        let check = || {
+            new(Crates, PKG_NAME, CURRENT_VERSION)
+                .cache_storage(storage.clone())
+                .clock(clock.clone())
+                .check_version()
+        };
+        let version = Version::parse(LATEST_VERSION).expect("parse version");
+
+        // The first check only starts the interval
+        assert_eq!(check().unwrap(), None);
+
+        clock.advance(DAY - Duration::from_secs(1));
+        assert_eq!(check().unwrap(), None);
+
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(check().unwrap(), Some(version.clone()));
+        assert_eq!(cached(&storage), Some(LATEST_VERSION.to_string()));
+
+        // The new version is cached for another interval
+        clock.advance(DAY - Duration::from_secs(1));
+        assert_eq!(check().unwrap(), Some(version));
+
+        mock.assert();
     }
 
     #[test]
     fn check_version_with_string_name_test() {
-        within_test_dir(|_| {
-            let pkg_name = format!("{}/{}", "owner", PKG_NAME);
-            let informer = new(Crates, pkg_name, CURRENT_VERSION);
-            let result = informer.check_version();
+        let _globals = read_globals();
+        let pkg_name = format!("{}/{}", "owner", PKG_NAME);
+        let informer = new(Crates, pkg_name, CURRENT_VERSION).cache_storage(MemoryStorage::new());
+        let result = informer.check_version();
 
-            assert!(result.is_ok());
-        });
+        assert!(result.is_ok());
     }
 
     #[test]
     fn check_version_with_string_version_test() {
-        within_test_dir(|_| {
-            let version = String::from(CURRENT_VERSION);
-            let informer = new(Crates, PKG_NAME, version);
-            let result = informer.check_version();
+        let _globals = read_globals();
+        let version = String::from(CURRENT_VERSION);
+        let informer = new(Crates, PKG_NAME, version).cache_storage(MemoryStorage::new());
+        let result = informer.check_version();
 
-            assert!(result.is_ok());
-        });
+        assert!(result.is_ok());
     }
 
     #[test]
     fn check_version_with_amp_string_test() {
-        within_test_dir(|_| {
-            let pkg_name = format!("{}/{}", "owner", PKG_NAME);
-            let version = String::from(CURRENT_VERSION);
-            let informer = new(Crates, &pkg_name, &version);
-            let result = informer.check_version();
+        let _globals = read_globals();
+        let pkg_name = format!("{}/{}", "owner", PKG_NAME);
+        let version = String::from(CURRENT_VERSION);
+        let informer = new(Crates, &pkg_name, &version).cache_storage(MemoryStorage::new());
+        let result = informer.check_version();
 
-            assert!(result.is_ok());
-        });
+        assert!(result.is_ok());
     }
 
     #[test]
@@ -454,4 +802,72 @@ mod tests {
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), Some(version));
     }
+
+    #[test]
+    fn fake_outcomes_test() {
+        let informer = fake(Crates, PKG_NAME, CURRENT_VERSION, LATEST_VERSION).no_update();
+        assert_eq!(informer.check_version().unwrap(), None);
+
+        let informer =
+            fake(Crates, PKG_NAME, CURRENT_VERSION, LATEST_VERSION).error(HttpError::Html);
+        let error = informer.check_version().unwrap_err();
+        assert_eq!(error.downcast_ref(), Some(&HttpError::Html));
+
+        let informer = fake(Crates, PKG_NAME, CURRENT_VERSION, LATEST_VERSION)
+            .latency(Duration::from_secs(1))
+            .timeout(Duration::from_millis(10));
+        let error = informer.check_version().unwrap_err();
+        assert_eq!(error.downcast_ref(), Some(&HttpError::DeadlineExceeded));
+    }
+
+    /// Removes the environment variable even if the test panics.
+    struct FakeEnv;
+
+    impl FakeEnv {
+        fn set(value: &str) -> Self {
+            std::env::set_var("UPDATE_INFORMER_FAKE", value);
+            Self
+        }
+    }
+
+    impl Drop for FakeEnv {
+        fn drop(&mut self) {
+            std::env::remove_var("UPDATE_INFORMER_FAKE");
+        }
+    }
+
+    #[test]
+    fn fake_env_test() {
+        let _globals = write_globals();
+        let mock = mock_crates(PKG_NAME).expect(0);
+        let check = || {
+            new(Crates, PKG_NAME, CURRENT_VERSION)
+                .cache_storage(MemoryStorage::new())
+                .check_version()
+        };
+
+        let _env = FakeEnv::set("update");
+        let version = Version::parse(LATEST_VERSION).expect("parse version");
+        assert_eq!(check().unwrap(), Some(version));
+
+        let _env = FakeEnv::set("update:4.0.0");
+        let version = Version::parse("4.0.0").expect("parse version");
+        assert_eq!(check().unwrap(), Some(version));
+
+        let _env = FakeEnv::set("none");
+        assert_eq!(check().unwrap(), None);
+
+        let _env = FakeEnv::set("error:503");
+        let error = check().unwrap_err();
+        assert_eq!(error.downcast_ref(), Some(&HttpError::Status(503)));
+
+        let _env = FakeEnv::set("timeout");
+        let error = check().unwrap_err();
+        assert_eq!(error.downcast_ref(), Some(&HttpError::DeadlineExceeded));
+
+        let _env = FakeEnv::set("unknown");
+        assert!(check().is_err());
+
+        mock.assert();
+    }
 }
@@ -0,0 +1,38 @@
+use std::{
+    env,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+/// Disables all update checks, e.g. when the application is run with `--offline`.
+///
+/// While offline, checks return no new version without sending requests or accessing the
+/// cache. Update checks are also disabled by setting the `UPDATE_INFORMER_OFFLINE`
+/// environment variable to any value except `0` or `false`.
+///
+/// # Examples
+///
+/// ```rust
+/// use update_informer::{registry, Check};
+///
+/// update_informer::set_offline(true);
+///
+/// let informer = update_informer::new(registry::Crates, "crate_name", "0.1.0");
+/// assert_eq!(informer.check_version().unwrap(), None);
+/// ```
+pub fn set_offline(offline: bool) {
+    OFFLINE.store(offline, Ordering::Relaxed);
+}
+
+/// Returns `true` if update checks are disabled.
+pub(crate) fn is_offline() -> bool {
+    if OFFLINE.load(Ordering::Relaxed) {
+        return true;
+    }
+
+    match env::var("UPDATE_INFORMER_OFFLINE") {
+        Ok(value) => !matches!(value.trim(), "" | "0" | "false"),
+        Err(_) => false,
+    }
+}
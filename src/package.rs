@@ -1,4 +1,4 @@
-use crate::{Result, Version};
+use crate::{Result, Version, VersionScheme};
 use std::fmt::{Display, Formatter};
 
 /// A package representation.
@@ -10,23 +10,29 @@ pub struct Package<'a> {
 }
 
 impl<'a> Package<'a> {
+    #[cfg(test)]
     pub(crate) fn new(name: &'a str, version: &'a str) -> Result<Self> {
-        let version = Version::parse(version)?;
+        Self::with_scheme(name, version, VersionScheme::Semver)
+    }
+
+    pub(crate) fn with_scheme(
+        name: &'a str,
+        version: &'a str,
+        scheme: VersionScheme,
+    ) -> Result<Self> {
+        let version = Version::parse_with_scheme(version, scheme)?;
 
-        let pkg = if !name.contains('/') {
-            Self {
+        let pkg = match name.split_once('/') {
+            None => Self {
                 owner: None,
                 name,
                 version,
-            }
-        } else {
-            let parts = name.split('/').collect::<Vec<_>>();
-
-            Self {
-                owner: Some(parts[0]),
-                name: parts[1],
+            },
+            Some((owner, name)) => Self {
+                owner: Some(owner),
+                name,
                 version,
-            }
+            },
         };
 
         Ok(pkg)
@@ -36,7 +42,7 @@ impl<'a> Package<'a> {
     /// owner if it is set.
     pub(crate) fn name(&self) -> String {
         let owner = self.owner.map(|s| format!("{s}-")).unwrap_or_default();
-        format!("{}{}", owner, self.name)
+        format!("{}{}", owner, self.name.replace('/', "-"))
     }
 
     /// Returns the parsed version of the package
@@ -90,6 +96,16 @@ mod tests {
         assert_eq!(pkg1.name(), "owner-repo".to_string());
     }
 
+    #[test]
+    fn new_with_nested_name_test() {
+        let pkg = Package::new("github.com/owner/repo", RAW_VERSION).unwrap();
+
+        assert_eq!(pkg.owner, Some("github.com"));
+        assert_eq!(pkg.name, "owner/repo");
+        assert_eq!(pkg.name(), "github.com-owner-repo".to_string());
+        assert_eq!(format!("{}", pkg), "github.com/owner/repo");
+    }
+
     #[test]
     fn name_fmt_test() {
         let pkg = Package::new("repo", RAW_VERSION).unwrap();
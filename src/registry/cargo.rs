@@ -0,0 +1,382 @@
+use crate::{
+    http_client::{GenericHttpClient, HttpClient},
+    registry::{
+        cache_name,
+        crates_index::{index_path, latest_version},
+    },
+    Package, Registry, Result,
+};
+use serde::Deserialize;
+
+/// The `config.json` file at the root of a sparse index.
+#[derive(Deserialize)]
+struct IndexConfig {
+    #[serde(rename = "auth-required", default)]
+    auth_required: bool,
+}
+
+/// An alternate Cargo registry using the sparse index protocol, e.g. Kellnr, Cloudsmith
+/// or any other registry with a `sparse+https://...` index URL.
+///
+/// The token is sent only if the `config.json` of the index requires authentication or
+/// cannot be fetched anonymously, as Cargo does. Yanked versions are skipped and
+/// pre-releases are only considered if the current version is a pre-release.
+///
+/// # Examples
+///
+/// ```rust
+/// use update_informer::{registry::CargoRegistry, Check};
+///
+/// let registry = CargoRegistry::new("sparse+https://cargo.example.com/index/").token("secret");
+///
+/// let informer = update_informer::new(registry, "crate_name", "0.1.0");
+/// let _ = informer.check_version();
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CargoRegistry {
+    index_url: String,
+    token: Option<String>,
+}
+
+impl CargoRegistry {
+    /// Constructs a new `CargoRegistry`.
+    ///
+    /// # Arguments
+    ///
+    /// * `index_url` - A sparse index URL with or without the `sparse+` prefix.
+    pub fn new<U: AsRef<str>>(index_url: U) -> Self {
+        let index_url = index_url.as_ref();
+        let index_url = index_url.strip_prefix("sparse+").unwrap_or(index_url);
+
+        Self {
+            index_url: index_url.trim_end_matches('/').to_string(),
+            token: None,
+        }
+    }
+
+    /// Constructs a new `CargoRegistry` from a registry defined in Cargo configuration.
+    ///
+    /// The index URL is taken from `CARGO_REGISTRIES_<NAME>_INDEX` or `[registries.<name>]`
+    /// in `.cargo/config.toml` (searched from the current directory upwards, then in
+    /// `$CARGO_HOME`). The token is taken from `CARGO_REGISTRIES_<NAME>_TOKEN` or
+    /// `$CARGO_HOME/credentials.toml`. Only sparse registries are supported.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the registry, e.g. `my-registry`.
+    #[cfg(feature = "cargo-config")]
+    pub fn from_cargo_config(name: &str) -> Result<Self> {
+        let index_url = config::index_url(name)?
+            .ok_or_else(|| format!("registry `{name}` is not defined in Cargo configuration"))?;
+
+        if !index_url.starts_with("sparse+") {
+            return Err(format!("registry `{name}` does not use the sparse protocol").into());
+        }
+
+        let registry = Self::new(index_url);
+        match config::token(name)? {
+            Some(token) => Ok(registry.token(token)),
+            None => Ok(registry),
+        }
+    }
+
+    /// Sets a token sent in the `Authorization` header if the registry requires authentication.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - A registry token.
+    pub fn token<T: Into<String>>(self, token: T) -> Self {
+        Self {
+            token: Some(token.into()),
+            ..self
+        }
+    }
+}
+
+impl Registry for CargoRegistry {
+    const NAME: &'static str = "cargo";
+
+    /// Returns e.g. `cargo-cargo.example.com-1a2b3c4d`, identifying the index URL.
+    fn cache_name(&self) -> String {
+        cache_name::with_url(Self::NAME, &self.index_url, "")
+    }
+
+    fn get_latest_version<T: HttpClient>(
+        &self,
+        http_client: GenericHttpClient<T>,
+        pkg: &Package,
+    ) -> Result<Option<String>> {
+        let config_url = format!("{}/config.json", self.index_url);
+        let http_client = match (http_client.get::<IndexConfig>(&config_url), &self.token) {
+            (Ok(config), Some(token)) if config.auth_required => {
+                http_client.add_header("Authorization", token)
+            }
+            (Ok(_), _) => http_client,
+            // Registries requiring authentication may reject anonymous requests for `config.json`
+            (Err(_), Some(token)) => {
+                let http_client = http_client.add_header("Authorization", token);
+                http_client.get::<IndexConfig>(&config_url)?;
+                http_client
+            }
+            (Err(e), None) => return Err(e),
+        };
+
+        let url = format!("{}/{}", self.index_url, index_path(&pkg.to_string())?);
+        let index = http_client.get_text(&url)?;
+
+        let prereleases = !pkg.version().semver().pre.is_empty();
+
+        latest_version(&index, prereleases)
+    }
+}
+
+#[cfg(feature = "cargo-config")]
+mod config {
+    use crate::Result;
+    use serde::Deserialize;
+    use std::{
+        collections::HashMap,
+        env, fs,
+        io::ErrorKind,
+        path::{Path, PathBuf},
+    };
+
+    #[derive(Deserialize, Default)]
+    struct File {
+        #[serde(default)]
+        registries: HashMap<String, RegistryConfig>,
+    }
+
+    #[derive(Deserialize)]
+    struct RegistryConfig {
+        index: Option<String>,
+        token: Option<String>,
+    }
+
+    pub(super) fn index_url(name: &str) -> Result<Option<String>> {
+        if let Some(url) = env_var(name, "INDEX") {
+            return Ok(Some(url));
+        }
+
+        for path in config_paths() {
+            let index = read(&path)?
+                .registries
+                .remove(name)
+                .and_then(|registry| registry.index);
+
+            if index.is_some() {
+                return Ok(index);
+            }
+        }
+
+        Ok(None)
+    }
+
+    pub(super) fn token(name: &str) -> Result<Option<String>> {
+        if let Some(token) = env_var(name, "TOKEN") {
+            return Ok(Some(token));
+        }
+
+        let Some(cargo_home) = cargo_home() else {
+            return Ok(None);
+        };
+
+        for file_name in ["credentials.toml", "credentials"] {
+            let token = read(&cargo_home.join(file_name))?
+                .registries
+                .remove(name)
+                .and_then(|registry| registry.token);
+
+            if token.is_some() {
+                return Ok(token);
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Reads `CARGO_REGISTRIES_<NAME>_<KEY>`, e.g. `CARGO_REGISTRIES_MY_REGISTRY_TOKEN`.
+    fn env_var(name: &str, key: &str) -> Option<String> {
+        let name = name.to_uppercase().replace('-', "_");
+
+        env::var(format!("CARGO_REGISTRIES_{name}_{key}"))
+            .ok()
+            .filter(|v| !v.is_empty())
+    }
+
+    /// Returns configuration files in order of precedence.
+    fn config_paths() -> Vec<PathBuf> {
+        let mut dirs: Vec<PathBuf> = env::current_dir()
+            .map(|dir| dir.ancestors().map(|d| d.join(".cargo")).collect())
+            .unwrap_or_default();
+
+        if let Some(cargo_home) = cargo_home() {
+            if !dirs.contains(&cargo_home) {
+                dirs.push(cargo_home);
+            }
+        }
+
+        dirs.iter()
+            .flat_map(|dir| [dir.join("config.toml"), dir.join("config")])
+            .collect()
+    }
+
+    fn cargo_home() -> Option<PathBuf> {
+        if let Some(home) = env::var_os("CARGO_HOME") {
+            return Some(PathBuf::from(home));
+        }
+
+        env::var_os("HOME")
+            .or_else(|| env::var_os("USERPROFILE"))
+            .map(|home| Path::new(&home).join(".cargo"))
+    }
+
+    fn read(path: &Path) -> Result<File> {
+        match fs::read_to_string(path) {
+            Ok(contents) => Ok(toml::from_str(&contents)?),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(File::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::test_helper::{write_globals, EnvVar};
+
+        #[test]
+        fn read_config_test() {
+            let dir = env::temp_dir().join(format!(
+                "update-informer-cargo-config-{}",
+                std::process::id()
+            ));
+            fs::create_dir_all(&dir).expect("create test dir");
+
+            let path = dir.join("config.toml");
+            fs::write(
+                &path,
+                r#"
+                [build]
+                jobs = 4
+
+                [registries.my-registry]
+                index = "sparse+https://cargo.example.com/index/"
+                "#,
+            )
+            .expect("write config");
+
+            let mut file = read(&path).expect("read config");
+            let registry = file.registries.remove("my-registry").expect("get registry");
+            assert_eq!(
+                registry.index.as_deref(),
+                Some("sparse+https://cargo.example.com/index/")
+            );
+
+            fs::remove_dir_all(dir).expect("remove test dir");
+        }
+
+        #[test]
+        fn env_var_test() {
+            let _globals = write_globals();
+            let _env = EnvVar::set(
+                "CARGO_REGISTRIES_UPDATE_INFORMER_TEST_INDEX",
+                "sparse+http://host/",
+            );
+
+            assert_eq!(
+                index_url("update-informer-test").unwrap(),
+                Some("sparse+http://host/".to_string())
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http_client;
+    use mockito::{mock, Matcher};
+    use std::{fs, time::Duration};
+
+    const PKG_NAME: &str = "repo";
+    const FIXTURES_PATH: &str = "tests/fixtures/registry/crates/index";
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    #[test]
+    fn success_with_auth_test() {
+        let pkg = Package::new(PKG_NAME, "0.1.0").unwrap();
+        let client = http_client::new(http_client::DefaultHttpClient::default(), TIMEOUT);
+        let data = fs::read_to_string(format!("{}/re/po/repo", FIXTURES_PATH)).unwrap();
+
+        let _config = mock("GET", "/private/config.json")
+            .match_header("Authorization", "secret")
+            .with_status(200)
+            .with_body(r#"{"dl":"https://cargo.example.com/dl","auth-required":true}"#)
+            .create();
+        let _index = mock("GET", "/private/re/po/repo")
+            .match_header("Authorization", "secret")
+            .with_status(200)
+            .with_body(data)
+            .create();
+
+        let index_url = format!("sparse+{}/private/", mockito::server_url());
+        let registry = CargoRegistry::new(index_url).token("secret");
+        let result = registry.get_latest_version(client, &pkg);
+
+        assert_eq!(result.expect("get result"), Some("0.2.1".to_string()));
+    }
+
+    #[test]
+    fn token_not_sent_to_public_index_test() {
+        let pkg = Package::new(PKG_NAME, "0.1.0").unwrap();
+        let client = http_client::new(http_client::DefaultHttpClient::default(), TIMEOUT);
+        let data = fs::read_to_string(format!("{}/re/po/repo", FIXTURES_PATH)).unwrap();
+
+        let _config = mock("GET", "/public/config.json")
+            .with_status(200)
+            .with_body(r#"{"dl":"https://cargo.example.com/dl"}"#)
+            .create();
+        let _index = mock("GET", "/public/re/po/repo")
+            .match_header("Authorization", Matcher::Missing)
+            .with_status(200)
+            .with_body(data)
+            .create();
+
+        let index_url = format!("sparse+{}/public", mockito::server_url());
+        let registry = CargoRegistry::new(index_url).token("secret");
+        let result = registry.get_latest_version(client, &pkg);
+
+        assert_eq!(result.expect("get result"), Some("0.2.1".to_string()));
+    }
+
+    #[test]
+    fn cache_name_test() {
+        let registry = CargoRegistry::new("sparse+https://cargo.example.com/index/");
+        let cache_name = registry.cache_name();
+        assert!(
+            cache_name.starts_with("cargo-cargo.example.com-"),
+            "{cache_name}"
+        );
+        assert_eq!(
+            CargoRegistry::new("https://cargo.example.com/index").cache_name(),
+            cache_name
+        );
+
+        let mirror = CargoRegistry::new("sparse+https://mirror.example.com/index/");
+        assert_ne!(mirror.cache_name(), cache_name);
+    }
+
+    #[test]
+    fn missing_config_test() {
+        let pkg = Package::new(PKG_NAME, "0.1.0").unwrap();
+        let client = http_client::new(http_client::DefaultHttpClient::default(), TIMEOUT);
+        let _config = mock("GET", "/missing/config.json")
+            .with_status(404)
+            .create();
+
+        let index_url = format!("sparse+{}/missing", mockito::server_url());
+        let result = CargoRegistry::new(index_url).get_latest_version(client, &pkg);
+
+        assert!(result.is_err());
+    }
+}
@@ -37,6 +37,7 @@ impl Registry for Crates {
     const NAME: &'static str = "crates";
 
     fn get_latest_version<T: HttpClient>(
+        &self,
         http_client: GenericHttpClient<T>,
         pkg: &Package,
     ) -> Result<Option<String>> {
@@ -65,17 +66,17 @@ mod tests {
     #[test]
     fn failure_test() {
         let pkg = Package::new(PKG_NAME, RAW_VERSION).unwrap();
-        let client = http_client::new(http_client::DefaultHttpClient {}, TIMEOUT);
+        let client = http_client::new(http_client::DefaultHttpClient::default(), TIMEOUT);
         let data_path = format!("{}/not_found.json", FIXTURES_PATH);
         let _mock = mock_crates(&pkg, 404, &data_path);
-        let result = Crates::get_latest_version(client, &pkg);
+        let result = Crates.get_latest_version(client, &pkg);
         assert!(result.is_err());
     }
 
     #[test]
     fn success_test() {
         let pkg = Package::new(PKG_NAME, RAW_VERSION).unwrap();
-        let client = http_client::new(http_client::DefaultHttpClient {}, TIMEOUT);
+        let client = http_client::new(http_client::DefaultHttpClient::default(), TIMEOUT);
         let data_path = format!("{}/versions.json", FIXTURES_PATH);
         let (_mock, data) = mock_crates(&pkg, 200, &data_path);
 
@@ -87,7 +88,7 @@ mod tests {
             .num
             .clone();
 
-        let result = Crates::get_latest_version(client, &pkg);
+        let result = Crates.get_latest_version(client, &pkg);
 
         assert!(result.is_ok());
         assert_eq!(result.expect("get result"), Some(latest_version));
@@ -0,0 +1,176 @@
+use crate::{
+    http_client::{GenericHttpClient, HttpClient},
+    Package, Registry, Result, Version,
+};
+use serde::Deserialize;
+
+#[cfg(not(test))]
+const INDEX_URL: &str = "https://index.crates.io";
+
+/// A line of an index file, one per published version.
+#[derive(Deserialize)]
+struct IndexEntry {
+    vers: String,
+    #[serde(default)]
+    yanked: bool,
+}
+
+/// The crates.io sparse index, an alternative to the crates.io web API used by [`Crates`](crate::registry::Crates).
+///
+/// The index is served from a CDN, is not subject to the crawler policy of the web API and
+/// includes information about yanked versions, which are skipped. Pre-releases are only
+/// considered if the current version is a pre-release.
+///
+/// # Examples
+///
+/// ```rust
+/// use update_informer::{registry, Check};
+///
+/// let informer = update_informer::new(registry::CratesIndex, "crate_name", "0.1.0");
+/// let _ = informer.check_version();
+/// ```
+pub struct CratesIndex;
+
+#[cfg(not(test))]
+fn get_base_url() -> String {
+    INDEX_URL.to_string()
+}
+
+#[cfg(test)]
+fn get_base_url() -> String {
+    mockito::server_url()
+}
+
+impl Registry for CratesIndex {
+    const NAME: &'static str = "crates";
+
+    fn get_latest_version<T: HttpClient>(
+        &self,
+        http_client: GenericHttpClient<T>,
+        pkg: &Package,
+    ) -> Result<Option<String>> {
+        let url = format!("{}/{}", get_base_url(), index_path(&pkg.to_string())?);
+        let index = http_client.get_text(&url)?;
+
+        let prereleases = !pkg.version().semver().pre.is_empty();
+
+        latest_version(&index, prereleases)
+    }
+}
+
+/// Returns the path of the index file for a crate, e.g. `se/rd/serde` or `3/s/syn`.
+///
+/// Crate names are ASCII only, so other names are rejected.
+pub(crate) fn index_path(name: &str) -> Result<String> {
+    if name.is_empty() || !name.is_ascii() {
+        return Err(format!("invalid crate name: {name}").into());
+    }
+
+    let name = name.to_ascii_lowercase();
+
+    let path = match name.len() {
+        1 => format!("1/{name}"),
+        2 => format!("2/{name}"),
+        3 => format!("3/{}/{name}", &name[..1]),
+        _ => format!("{}/{}/{name}", &name[..2], &name[2..4]),
+    };
+
+    Ok(path)
+}
+
+/// Returns the highest version of an index file that is not yanked.
+///
+/// # Arguments
+///
+/// * `index` - The contents of an index file, one JSON object per line.
+/// * `prereleases` - Whether to include pre-releases.
+pub(crate) fn latest_version(index: &str, prereleases: bool) -> Result<Option<String>> {
+    let mut latest: Option<(Version, String)> = None;
+
+    for line in index.lines().filter(|line| !line.trim().is_empty()) {
+        let entry: IndexEntry = serde_json::from_str(line)?;
+        if entry.yanked {
+            continue;
+        }
+
+        let Ok(version) = Version::parse(&entry.vers) else {
+            continue;
+        };
+        if !prereleases && !version.semver().pre.is_empty() {
+            continue;
+        }
+
+        if latest.as_ref().is_none_or(|(v, _)| &version > v) {
+            latest = Some((version, entry.vers));
+        }
+    }
+
+    Ok(latest.map(|(_, raw)| raw))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{http_client, test_helper::mock_crates_index};
+    use std::time::Duration;
+
+    const PKG_NAME: &str = "repo";
+    const FIXTURES_PATH: &str = "tests/fixtures/registry/crates/index";
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    #[test]
+    fn failure_test() {
+        let pkg = Package::new("missing", "0.1.0").unwrap();
+        let client = http_client::new(http_client::DefaultHttpClient::default(), TIMEOUT);
+        let data_path = format!("{}/not_found.txt", FIXTURES_PATH);
+        let _mock = mock_crates_index(&pkg, 404, &data_path);
+
+        let result = CratesIndex.get_latest_version(client, &pkg);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn success_test() {
+        let pkg = Package::new(PKG_NAME, "0.1.0").unwrap();
+        let client = http_client::new(http_client::DefaultHttpClient::default(), TIMEOUT);
+        let data_path = format!("{}/re/po/repo", FIXTURES_PATH);
+        let _mock = mock_crates_index(&pkg, 200, &data_path);
+
+        let result = CratesIndex.get_latest_version(client, &pkg);
+
+        assert!(result.is_ok());
+        assert_eq!(result.expect("get result"), Some("0.2.1".to_string()));
+    }
+
+    #[test]
+    fn prerelease_test() {
+        let pkg = Package::new(PKG_NAME, "0.1.0-beta.1").unwrap();
+        let client = http_client::new(http_client::DefaultHttpClient::default(), TIMEOUT);
+        let data_path = format!("{}/re/po/repo", FIXTURES_PATH);
+        let _mock = mock_crates_index(&pkg, 200, &data_path);
+
+        let result = CratesIndex.get_latest_version(client, &pkg);
+
+        assert!(result.is_ok());
+        assert_eq!(result.expect("get result"), Some("0.3.0-rc.1".to_string()));
+    }
+
+    #[test]
+    fn index_path_test() {
+        assert_eq!(index_path("a").unwrap(), "1/a");
+        assert_eq!(index_path("ab").unwrap(), "2/ab");
+        assert_eq!(index_path("abc").unwrap(), "3/a/abc");
+        assert_eq!(index_path("Serde").unwrap(), "se/rd/serde");
+    }
+
+    #[test]
+    fn invalid_name_test() {
+        assert!(index_path("").is_err());
+        assert!(index_path("é").is_err());
+        assert!(index_path("sérde").is_err());
+
+        let pkg = Package::new("ключ", "0.1.0").unwrap();
+        let client = http_client::new(http_client::DefaultHttpClient::default(), TIMEOUT);
+        assert!(CratesIndex.get_latest_version(client, &pkg).is_err());
+    }
+}
@@ -0,0 +1,434 @@
+use crate::{
+    http_client::{GenericHttpClient, HttpClient, HttpError},
+    registry::cache_name,
+    Error, Package, Registry, Release, Result, VersionScheme,
+};
+use std::ops::ControlFlow;
+
+/// A list of registries used by [`Fallback`] and [`MultiRegistry`](crate::registry::MultiRegistry).
+///
+/// Implemented for tuples of up to four registries, e.g. `(GitHub, Crates)`, and for
+/// lists of registries of the same type, e.g. mirrors with different base URLs.
+pub trait RegistryList {
+    /// Gets the latest release from the first registry that answers.
+    fn get_latest_release<T: HttpClient>(
+        &self,
+        http_client: &GenericHttpClient<T>,
+        pkg: &Package,
+    ) -> Result<Option<Release>>;
+
+    /// Returns a name for each registry: the name of the registry for tuples, e.g. `crates`,
+    /// or the name and the position in the list, e.g. `oci#1`.
+    fn sources(&self) -> Vec<String>;
+
+    /// Returns the [cache name](Registry::cache_name) of each registry.
+    fn cache_names(&self) -> Vec<String>;
+
+    /// Gets the latest release from the registry at the given position.
+    fn get_latest_release_at<T: HttpClient>(
+        &self,
+        index: usize,
+        http_client: GenericHttpClient<T>,
+        pkg: &Package,
+    ) -> Result<Option<Release>>;
+
+    /// Returns the versioning scheme of the first registry.
+    fn version_scheme(&self) -> VersionScheme;
+
+    /// Returns the versioning scheme of the registry at the given position.
+    fn version_scheme_at(&self, index: usize) -> VersionScheme;
+}
+
+/// A registry that tries several registries in order until one of them answers.
+///
+/// The next registry is tried if a request fails with a transport error (e.g. a timeout
+/// or a blocked connection, see [`HttpError::Connect`]), a server error (5xx), a request
+/// timeout (408) or because of rate limiting (403 or 429). Other errors, e.g. an unknown
+/// package (404) or an invalid response, are returned immediately.
+///
+/// Each registry gets an equal share of the time left for the check, so a registry that
+/// does not answer leaves time for the next ones; the last registry gets all the time left.
+///
+/// The registry that answered is recorded in [`Release::source`]: the name of the registry
+/// for tuples, e.g. `crates`, or the name and the position in the list, e.g. `oci#1`.
+///
+/// # Examples
+///
+/// ```rust
+/// use update_informer::{registry::{Crates, CratesIndex, Fallback}, Check};
+///
+/// let registry = Fallback::new((CratesIndex, Crates));
+///
+/// let informer = update_informer::new(registry, "crate_name", "0.1.0");
+/// if let Ok(Some(update)) = informer.check_update() {
+///     println!("{} from {:?}", update.version(), update.release().source);
+/// }
+/// ```
+///
+/// With mirrors of the same registry:
+///
+/// ```rust
+/// use update_informer::{registry::{CargoRegistry, Fallback}, Check};
+///
+/// let registry = Fallback::new(vec![
+///     CargoRegistry::new("sparse+https://index.crates.io/"),
+///     CargoRegistry::new("sparse+https://mirror.example.com/index/"),
+/// ]);
+///
+/// let informer = update_informer::new(registry, "crate_name", "0.1.0");
+/// let _ = informer.check_version();
+/// ```
+pub struct Fallback<L: RegistryList> {
+    registries: L,
+}
+
+impl<L: RegistryList> Fallback<L> {
+    /// Constructs a new `Fallback`.
+    ///
+    /// # Arguments
+    ///
+    /// * `registries` - A tuple or a list of registries in the order they are tried.
+    pub fn new(registries: L) -> Self {
+        Self { registries }
+    }
+}
+
+impl<L: RegistryList> Registry for Fallback<L> {
+    const NAME: &'static str = "fallback";
+
+    fn cache_name(&self) -> String {
+        cache_name::with_registries(Self::NAME, &self.registries.cache_names(), "")
+    }
+
+    fn get_latest_version<T: HttpClient>(
+        &self,
+        http_client: GenericHttpClient<T>,
+        pkg: &Package,
+    ) -> Result<Option<String>> {
+        let release = self.get_latest_release(http_client, pkg)?;
+
+        Ok(release.map(|r| r.version))
+    }
+
+    fn get_latest_release<T: HttpClient>(
+        &self,
+        http_client: GenericHttpClient<T>,
+        pkg: &Package,
+    ) -> Result<Option<Release>> {
+        self.registries.get_latest_release(&http_client, pkg)
+    }
+
+    fn version_scheme(&self) -> VersionScheme {
+        self.registries.version_scheme()
+    }
+}
+
+/// Records the registry that answered, keeping the source of nested registries.
+pub(crate) fn with_source(release: Release, source: String) -> Release {
+    Release {
+        source: release.source.or(Some(source)),
+        ..release
+    }
+}
+
+/// Asks a registry and decides whether the next registry should be tried.
+///
+/// The registry gets an equal share of the time left for the `left` registries still to try.
+fn attempt<R: Registry, T: HttpClient>(
+    registry: &R,
+    http_client: &GenericHttpClient<T>,
+    pkg: &Package,
+    source: String,
+    left: usize,
+) -> ControlFlow<Result<Option<Release>>, Error> {
+    let share = http_client.remaining() / left.max(1) as u32;
+
+    match registry.get_latest_release(http_client.with_timeout(share), pkg) {
+        Ok(release) => ControlFlow::Break(Ok(release.map(|r| with_source(r, source)))),
+        Err(e) if is_transient(&e) => ControlFlow::Continue(e),
+        Err(e) => ControlFlow::Break(Err(e)),
+    }
+}
+
+/// Returns `true` for errors that another registry may avoid: transport errors, timeouts,
+/// server errors and rate limiting. Other errors, e.g. 404 or invalid JSON, would be the same
+/// on any registry.
+fn is_transient(error: &Error) -> bool {
+    match error.downcast_ref::<HttpError>() {
+        Some(HttpError::Status(status)) => *status >= 500 || matches!(status, 403 | 408 | 429),
+        Some(HttpError::Connect(_) | HttpError::DeadlineExceeded) => true,
+        _ => false,
+    }
+}
+
+impl<R: Registry> RegistryList for Vec<R> {
+    fn get_latest_release<T: HttpClient>(
+        &self,
+        http_client: &GenericHttpClient<T>,
+        pkg: &Package,
+    ) -> Result<Option<Release>> {
+        let mut last_error = None;
+
+        for (index, registry) in self.iter().enumerate() {
+            let source = format!("{}#{index}", R::NAME);
+
+            match attempt(registry, http_client, pkg, source, self.len() - index) {
+                ControlFlow::Break(result) => return result,
+                ControlFlow::Continue(e) => last_error = Some(e),
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| "no registries to check".into()))
+    }
+
+    fn sources(&self) -> Vec<String> {
+        (0..self.len())
+            .map(|index| format!("{}#{index}", R::NAME))
+            .collect()
+    }
+
+    fn cache_names(&self) -> Vec<String> {
+        self.iter().map(Registry::cache_name).collect()
+    }
+
+    fn get_latest_release_at<T: HttpClient>(
+        &self,
+        index: usize,
+        http_client: GenericHttpClient<T>,
+        pkg: &Package,
+    ) -> Result<Option<Release>> {
+        let registry = self.get(index).ok_or("no registry at this position")?;
+
+        registry.get_latest_release(http_client, pkg)
+    }
+
+    fn version_scheme(&self) -> VersionScheme {
+        self.version_scheme_at(0)
+    }
+
+    fn version_scheme_at(&self, index: usize) -> VersionScheme {
+        self.get(index)
+            .map(Registry::version_scheme)
+            .unwrap_or_default()
+    }
+}
+
+macro_rules! impl_registry_list {
+    ($($name:ident $index:tt),+) => {
+        impl<$($name: Registry),+> RegistryList for ($($name,)+) {
+            #[allow(non_snake_case)]
+            fn get_latest_release<T: HttpClient>(
+                &self,
+                http_client: &GenericHttpClient<T>,
+                pkg: &Package,
+            ) -> Result<Option<Release>> {
+                let ($($name,)+) = self;
+                let attempts: &[&dyn Fn(usize) -> ControlFlow<Result<Option<Release>>, Error>] = &[
+                    $(&|left| attempt($name, http_client, pkg, $name::NAME.to_string(), left)),+
+                ];
+
+                let mut last_error = None;
+                for (index, next) in attempts.iter().enumerate() {
+                    match next(attempts.len() - index) {
+                        ControlFlow::Break(result) => return result,
+                        ControlFlow::Continue(e) => last_error = Some(e),
+                    }
+                }
+
+                Err(last_error.unwrap_or_else(|| "no registries to check".into()))
+            }
+
+            fn sources(&self) -> Vec<String> {
+                vec![$($name::NAME.to_string()),+]
+            }
+
+            fn cache_names(&self) -> Vec<String> {
+                vec![$(self.$index.cache_name()),+]
+            }
+
+            fn get_latest_release_at<T: HttpClient>(
+                &self,
+                index: usize,
+                http_client: GenericHttpClient<T>,
+                pkg: &Package,
+            ) -> Result<Option<Release>> {
+                match index {
+                    $($index => self.$index.get_latest_release(http_client, pkg),)+
+                    _ => Err("no registry at this position".into()),
+                }
+            }
+
+            fn version_scheme(&self) -> VersionScheme {
+                self.0.version_scheme()
+            }
+
+            fn version_scheme_at(&self, index: usize) -> VersionScheme {
+                match index {
+                    $($index => self.$index.version_scheme(),)+
+                    _ => VersionScheme::default(),
+                }
+            }
+        }
+    };
+}
+
+impl_registry_list!(A 0, B 1);
+impl_registry_list!(A 0, B 1, C 2);
+impl_registry_list!(A 0, B 1, C 2, D 3);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{http_client, test_helper::mock_http, testing::MockHttpClient};
+    use serde::Deserialize;
+    use std::time::Duration;
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    #[derive(Deserialize)]
+    struct Response {
+        version: String,
+    }
+
+    /// A registry reading `{"version": "..."}` from a path of the mock server.
+    struct Endpoint(&'static str);
+
+    impl Registry for Endpoint {
+        const NAME: &'static str = "endpoint";
+
+        fn get_latest_version<T: HttpClient>(
+            &self,
+            http_client: GenericHttpClient<T>,
+            _pkg: &Package,
+        ) -> Result<Option<String>> {
+            let url = format!("{}{}", mockito::server_url(), self.0);
+            let resp = http_client.get::<Response>(&url)?;
+
+            Ok(Some(resp.version))
+        }
+    }
+
+    fn get_latest_release<L: RegistryList>(registry: Fallback<L>) -> Result<Option<Release>> {
+        let pkg = Package::new("repo", "0.1.0").unwrap();
+        let client = http_client::new(http_client::DefaultHttpClient::default(), TIMEOUT);
+
+        registry.get_latest_release(client, &pkg)
+    }
+
+    #[test]
+    fn first_answers_test() {
+        let _first = mock_http("/first/ok", 200, r#"{"version":"1.0.0"}"#);
+        let _second = mock_http("/second/ok", 200, r#"{"version":"2.0.0"}"#);
+
+        let registry = Fallback::new((Endpoint("/first/ok"), Endpoint("/second/ok")));
+        let release = get_latest_release(registry).expect("get result");
+
+        let expected = Release {
+            source: Some("endpoint".to_string()),
+            ..Release::new("1.0.0")
+        };
+        assert_eq!(release, Some(expected));
+    }
+
+    #[test]
+    fn server_error_test() {
+        let _first = mock_http("/first/unavailable", 503, "Service Unavailable");
+        let _second = mock_http("/second/rate-limited", 429, "Too Many Requests");
+        let _third = mock_http("/third/ok", 200, r#"{"version":"3.0.0"}"#);
+
+        let registry = Fallback::new(vec![
+            Endpoint("/first/unavailable"),
+            Endpoint("/second/rate-limited"),
+            Endpoint("/third/ok"),
+        ]);
+        let release = get_latest_release(registry).expect("get result");
+
+        let expected = Release {
+            source: Some("endpoint#2".to_string()),
+            ..Release::new("3.0.0")
+        };
+        assert_eq!(release, Some(expected));
+    }
+
+    #[test]
+    fn timeout_test() {
+        let pkg = Package::new("repo", "0.1.0").unwrap();
+        let slow = format!("{}/first/slow", mockito::server_url());
+        let ok = format!("{}/second/ok", mockito::server_url());
+        let mock = MockHttpClient::new()
+            .on_delayed(
+                &slow,
+                http_client::Response::new(200, r#"{"version":"1.0.0"}"#),
+                TIMEOUT,
+            )
+            .on(
+                &ok,
+                http_client::Response::new(200, r#"{"version":"2.0.0"}"#),
+            );
+        let client = http_client::new(mock.clone(), Duration::from_millis(200));
+
+        let registry = Fallback::new((Endpoint("/first/slow"), Endpoint("/second/ok")));
+        let release = registry
+            .get_latest_release(client, &pkg)
+            .expect("get result");
+
+        let expected = Release {
+            source: Some("endpoint".to_string()),
+            ..Release::new("2.0.0")
+        };
+        assert_eq!(release, Some(expected));
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[0].timeout <= Duration::from_millis(100));
+    }
+
+    #[test]
+    fn cache_name_test() {
+        let registry = Fallback::new((Endpoint("/first"), Endpoint("/second")));
+        let cache_name = registry.cache_name();
+        assert!(cache_name.starts_with("fallback-"), "{cache_name}");
+
+        let mirrors = Fallback::new(vec![Endpoint("/first"), Endpoint("/second")]);
+        assert_eq!(mirrors.cache_name(), cache_name);
+
+        let single = Fallback::new(vec![Endpoint("/first")]);
+        assert_ne!(single.cache_name(), cache_name);
+    }
+
+    #[test]
+    fn not_found_test() {
+        let _first = mock_http("/first/not-found", 404, "Not Found");
+        let second = mock_http("/second/unused", 200, r#"{"version":"2.0.0"}"#).expect(0);
+
+        let registry = Fallback::new((Endpoint("/first/not-found"), Endpoint("/second/unused")));
+        let result = get_latest_release(registry);
+
+        assert!(result.is_err());
+        second.assert();
+    }
+
+    #[test]
+    fn invalid_response_test() {
+        let _first = mock_http("/first/invalid", 200, r#"{"name":"repo"}"#);
+        let second = mock_http("/second/unused", 200, r#"{"version":"2.0.0"}"#).expect(0);
+
+        let registry = Fallback::new(vec![Endpoint("/first/invalid"), Endpoint("/second/unused")]);
+        let result = get_latest_release(registry);
+
+        assert!(result.is_err());
+        second.assert();
+    }
+
+    #[test]
+    fn all_fail_test() {
+        let _first = mock_http("/first/error", 500, "Internal Server Error");
+        let _second = mock_http("/second/error", 502, "Bad Gateway");
+
+        let registry = Fallback::new((Endpoint("/first/error"), Endpoint("/second/error")));
+        assert!(get_latest_release(registry).is_err());
+
+        let registry: Fallback<Vec<Endpoint>> = Fallback::new(vec![]);
+        assert!(get_latest_release(registry).is_err());
+    }
+}
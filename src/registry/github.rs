@@ -32,13 +32,13 @@ impl Registry for GitHub {
     const NAME: &'static str = "github";
 
     fn get_latest_version<T: HttpClient>(
+        &self,
         http_client: GenericHttpClient<T>,
         pkg: &Package,
     ) -> Result<Option<String>> {
         let url = format!("{}/{}/releases/latest", get_base_url(), pkg);
         let resp = http_client
             .add_header("Accept", "application/vnd.github.v3+json")
-            .add_header("User-Agent", "update-informer")
             .get::<Response>(&url)?;
 
         if resp.tag_name.starts_with('v') {
@@ -63,11 +63,11 @@ mod tests {
     fn failure_test() {
         let raw_version = "0.1.0";
         let pkg = Package::new(PKG_NAME, raw_version).unwrap();
-        let client = http_client::new(http_client::DefaultHttpClient {}, TIMEOUT);
+        let client = http_client::new(http_client::DefaultHttpClient::default(), TIMEOUT);
         let data_path = format!("{}/not_found.json", FIXTURES_PATH);
         let _mock = mock_github(&pkg, 404, &data_path);
 
-        let result = GitHub::get_latest_version(client, &pkg);
+        let result = GitHub.get_latest_version(client, &pkg);
         assert!(result.is_err());
     }
 
@@ -75,14 +75,14 @@ mod tests {
     fn success_test() {
         let raw_version = "1.6.3-canary.0";
         let pkg = Package::new(PKG_NAME, raw_version).unwrap();
-        let client = http_client::new(http_client::DefaultHttpClient {}, TIMEOUT);
+        let client = http_client::new(http_client::DefaultHttpClient::default(), TIMEOUT);
         let data_path = format!("{}/release.json", FIXTURES_PATH);
         let (_mock, data) = mock_github(&pkg, 200, &data_path);
 
         let json: Response = serde_json::from_str(&data).expect("deserialize json");
         let latest_version = json.tag_name[1..].to_string();
 
-        let result = GitHub::get_latest_version(client, &pkg);
+        let result = GitHub.get_latest_version(client, &pkg);
 
         assert!(result.is_ok());
         assert_eq!(result.expect("get result"), Some(latest_version));
@@ -0,0 +1,356 @@
+use crate::{
+    http_client::{GenericHttpClient, HttpClient},
+    Package, Registry, Result, Version,
+};
+use serde::Deserialize;
+use std::env;
+
+#[cfg(not(test))]
+const PROXY_URL: &str = "https://proxy.golang.org";
+
+#[derive(Deserialize)]
+struct Info {
+    #[serde(rename = "Version")]
+    version: String,
+}
+
+/// A Go module proxy, e.g. `proxy.golang.org`.
+///
+/// Tagged versions are taken from the `/@v/list` endpoint. If a module has no tagged versions,
+/// the pseudo-version from the `/@latest` endpoint is used, but only if the current version is
+/// a pre-release, since pseudo-versions are pre-releases. The `+incompatible` suffix is removed
+/// from versions, and module paths are passed as the package name, e.g. `github.com/owner/tool`.
+///
+/// # Examples
+///
+/// ```rust
+/// use update_informer::{registry::GoProxy, Check};
+///
+/// let informer = update_informer::new(GoProxy::from_env(), "github.com/owner/tool", "0.1.0");
+/// let _ = informer.check_version();
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GoProxy {
+    /// The proxy URL, or `None` if module downloads are turned off.
+    url: Option<String>,
+    private: Vec<String>,
+}
+
+impl GoProxy {
+    /// Constructs a new `GoProxy`.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - A module proxy URL, e.g. `https://goproxy.example.com`.
+    pub fn new<U: Into<String>>(url: U) -> Self {
+        Self {
+            url: Some(url.into().trim_end_matches('/').to_string()),
+            private: Vec::new(),
+        }
+    }
+
+    /// Constructs a new `GoProxy` from the environment variables of the `go` command.
+    ///
+    /// The first proxy URL from `GOPROXY` is used, skipping `direct` entries, or
+    /// `proxy.golang.org` if `GOPROXY` is not set. If `GOPROXY` is `off`, no version is found.
+    /// Modules matching `GONOPROXY`, or `GOPRIVATE` if it is not set, are not looked up,
+    /// so private module paths are not sent to the proxy.
+    pub fn from_env() -> Self {
+        let proxy = match env::var("GOPROXY") {
+            Ok(value) if !value.trim().is_empty() => match first_proxy(&value) {
+                Some(url) => Self::new(url),
+                None => Self {
+                    url: None,
+                    ..Self::default()
+                },
+            },
+            _ => Self::default(),
+        };
+
+        let private = env::var("GONOPROXY")
+            .ok()
+            .filter(|value| !value.trim().is_empty())
+            .or_else(|| env::var("GOPRIVATE").ok());
+        match private {
+            Some(patterns) => proxy.private(&patterns),
+            None => proxy,
+        }
+    }
+
+    /// Sets the module paths that are not looked up, like `GOPRIVATE`.
+    ///
+    /// # Arguments
+    ///
+    /// * `patterns` - Comma-separated glob patterns of module path prefixes,
+    ///   e.g. `*.corp.example.com,github.com/acme/*`.
+    pub fn private(self, patterns: &str) -> Self {
+        let private = patterns
+            .split(',')
+            .map(str::trim)
+            .filter(|pattern| !pattern.is_empty())
+            .map(str::to_string)
+            .collect();
+
+        Self { private, ..self }
+    }
+}
+
+impl Default for GoProxy {
+    fn default() -> Self {
+        Self::new(get_base_url())
+    }
+}
+
+#[cfg(not(test))]
+fn get_base_url() -> String {
+    PROXY_URL.to_string()
+}
+
+#[cfg(test)]
+fn get_base_url() -> String {
+    mockito::server_url()
+}
+
+impl Registry for GoProxy {
+    const NAME: &'static str = "goproxy";
+
+    fn get_latest_version<T: HttpClient>(
+        &self,
+        http_client: GenericHttpClient<T>,
+        pkg: &Package,
+    ) -> Result<Option<String>> {
+        let module = pkg.to_string();
+        let Some(base_url) = &self.url else {
+            return Ok(None);
+        };
+        if self
+            .private
+            .iter()
+            .any(|pattern| matches_prefix(pattern, &module))
+        {
+            return Ok(None);
+        }
+
+        let module = escape_path(&module);
+        let prereleases = !pkg.version().semver().pre.is_empty();
+
+        let url = format!("{base_url}/{module}/@v/list");
+        let list = http_client.get_text(&url)?;
+
+        if list.lines().any(|line| !line.trim().is_empty()) {
+            return Ok(latest_version(list.lines(), prereleases));
+        }
+
+        let url = format!("{base_url}/{module}/@latest");
+        let info = http_client.get::<Info>(&url)?;
+
+        Ok(latest_version([info.version.as_str()], prereleases))
+    }
+}
+
+/// Returns the first proxy URL of a `GOPROXY` value, e.g. `https://goproxy.io,direct`,
+/// or `None` if there is none before an `off` entry.
+fn first_proxy(value: &str) -> Option<String> {
+    value
+        .split([',', '|'])
+        .map(str::trim)
+        .take_while(|entry| *entry != "off")
+        .find(|entry| entry.starts_with("https://") || entry.starts_with("http://"))
+        .map(str::to_string)
+}
+
+/// Returns `true` if a glob pattern matches a prefix of the module path, as `GOPRIVATE`
+/// patterns do, e.g. `*.example.com` matches `git.example.com/team/tool`.
+fn matches_prefix(pattern: &str, module: &str) -> bool {
+    let mut elements = module.split('/');
+
+    pattern.trim_end_matches('/').split('/').all(|pattern| {
+        elements
+            .next()
+            .is_some_and(|element| glob_match(pattern.as_bytes(), element.as_bytes()))
+    })
+}
+
+/// Matches a path element against a pattern with `*` and `?` wildcards.
+fn glob_match(pattern: &[u8], value: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => value.is_empty(),
+        Some((b'*', rest)) => (0..=value.len()).any(|skip| glob_match(rest, &value[skip..])),
+        Some((b'?', rest)) => !value.is_empty() && glob_match(rest, &value[1..]),
+        Some((c, rest)) => value.first() == Some(c) && glob_match(rest, &value[1..]),
+    }
+}
+
+/// Escapes a module path for the proxy protocol by replacing uppercase letters
+/// with `!` followed by the lowercase letter, e.g. `github.com/BurntSushi/toml`
+/// becomes `github.com/!burnt!sushi/toml`.
+fn escape_path(path: &str) -> String {
+    let mut escaped = String::with_capacity(path.len());
+
+    for c in path.chars() {
+        if c.is_ascii_uppercase() {
+            escaped.push('!');
+            escaped.push(c.to_ascii_lowercase());
+        } else {
+            escaped.push(c);
+        }
+    }
+
+    escaped
+}
+
+/// Returns the highest version without the `+incompatible` suffix.
+fn latest_version<'a, I: IntoIterator<Item = &'a str>>(
+    versions: I,
+    prereleases: bool,
+) -> Option<String> {
+    versions
+        .into_iter()
+        .map(|v| v.trim().trim_end_matches("+incompatible"))
+        .filter_map(|v| Version::parse(v).ok().map(|version| (version, v)))
+        .filter(|(version, _)| prereleases || version.semver().pre.is_empty())
+        .max_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, v)| v.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        http_client,
+        test_helper::{mock_goproxy, mock_http, write_globals, EnvVar},
+    };
+    use std::{fs, time::Duration};
+
+    const FIXTURES_PATH: &str = "tests/fixtures/registry/goproxy";
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    #[test]
+    fn failure_test() {
+        let pkg = Package::new("github.com/owner/missing", "0.1.0").unwrap();
+        let client = http_client::new(http_client::DefaultHttpClient::default(), TIMEOUT);
+        let data_path = format!("{}/not_found.txt", FIXTURES_PATH);
+        let _mock = mock_goproxy("github.com/owner/missing", "@v/list", 404, &data_path);
+
+        let result = GoProxy::default().get_latest_version(client, &pkg);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn success_test() {
+        let pkg = Package::new("github.com/Owner/tool", "1.0.0").unwrap();
+        let client = http_client::new(http_client::DefaultHttpClient::default(), TIMEOUT);
+        let data_path = format!("{}/list.txt", FIXTURES_PATH);
+        let _mock = mock_goproxy("github.com/!owner/tool", "@v/list", 200, &data_path);
+
+        let result = GoProxy::default().get_latest_version(client, &pkg);
+
+        assert!(result.is_ok());
+        assert_eq!(result.expect("get result"), Some("v2.1.0".to_string()));
+    }
+
+    #[test]
+    fn pseudo_version_test() {
+        let pkg = Package::new("github.com/owner/untagged", "0.0.0-dev").unwrap();
+        let client = http_client::new(http_client::DefaultHttpClient::default(), TIMEOUT);
+        let _list = mock_http("/github.com/owner/untagged/@v/list", 200, "");
+        let data_path = format!("{}/latest.json", FIXTURES_PATH);
+        let _mock = mock_goproxy("github.com/owner/untagged", "@latest", 200, &data_path);
+
+        let result = GoProxy::default().get_latest_version(client, &pkg);
+
+        assert_eq!(
+            result.expect("get result"),
+            Some("v0.0.0-20240102150405-abcdef123456".to_string())
+        );
+    }
+
+    #[test]
+    fn latest_version_test() {
+        let list = fs::read_to_string(format!("{}/list.txt", FIXTURES_PATH)).unwrap();
+
+        assert_eq!(
+            latest_version(list.lines(), false),
+            Some("v2.1.0".to_string())
+        );
+        assert_eq!(
+            latest_version(list.lines(), true),
+            Some("v2.2.0-rc.1".to_string())
+        );
+        assert_eq!(
+            latest_version(["v0.0.0-20240102150405-abcdef123456"], false),
+            None
+        );
+    }
+
+    #[test]
+    fn escape_path_test() {
+        assert_eq!(
+            escape_path("github.com/BurntSushi/toml"),
+            "github.com/!burnt!sushi/toml"
+        );
+        assert_eq!(escape_path("golang.org/x/tools"), "golang.org/x/tools");
+    }
+
+    #[test]
+    fn first_proxy_test() {
+        assert_eq!(
+            first_proxy("direct,https://goproxy.io|https://proxy.golang.org"),
+            Some("https://goproxy.io".to_string())
+        );
+        assert_eq!(first_proxy("off"), None);
+        assert_eq!(first_proxy("off,https://proxy.golang.org"), None);
+    }
+
+    #[test]
+    fn matches_prefix_test() {
+        assert!(matches_prefix(
+            "*.corp.example.com",
+            "git.corp.example.com/team/tool"
+        ));
+        assert!(matches_prefix("github.com/acme", "github.com/acme/tool"));
+        assert!(matches_prefix(
+            "github.com/acme/*",
+            "github.com/acme/tool/v2"
+        ));
+        assert!(!matches_prefix(
+            "github.com/acme",
+            "github.com/acme-other/tool"
+        ));
+        assert!(!matches_prefix(
+            "github.com/acme/tool/v2",
+            "github.com/acme/tool"
+        ));
+    }
+
+    #[test]
+    fn private_test() {
+        let pkg = Package::new("github.com/acme/private", "1.0.0").unwrap();
+        let client = http_client::new(http_client::DefaultHttpClient::default(), TIMEOUT);
+        let mock = mock_http("/github.com/acme/private/@v/list", 200, "v2.0.0").expect(0);
+
+        let proxy = GoProxy::default().private("example.com,github.com/acme");
+        assert_eq!(
+            proxy.get_latest_version(client.clone(), &pkg).unwrap(),
+            None
+        );
+
+        let off = GoProxy {
+            url: None,
+            ..GoProxy::default()
+        };
+        assert_eq!(off.get_latest_version(client, &pkg).unwrap(), None);
+        mock.assert();
+    }
+
+    #[test]
+    fn from_env_test() {
+        let _globals = write_globals();
+        let _proxy = EnvVar::set("GOPROXY", "off");
+        let _private = EnvVar::set("GOPRIVATE", "github.com/acme");
+
+        let proxy = GoProxy::from_env();
+        assert_eq!(proxy.url, None);
+        assert_eq!(proxy.private, vec!["github.com/acme".to_string()]);
+    }
+}
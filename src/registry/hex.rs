@@ -0,0 +1,101 @@
+use crate::{
+    http_client::{GenericHttpClient, HttpClient},
+    Package, Registry, Result,
+};
+use serde::Deserialize;
+
+#[cfg(not(test))]
+const REGISTRY_URL: &str = "https://hex.pm";
+
+#[derive(Deserialize)]
+struct Response {
+    latest_stable_version: Option<String>,
+    latest_version: Option<String>,
+}
+
+/// The Hex package registry for Erlang and Elixir packages.
+///
+/// Pre-releases are only considered if the current version is a pre-release.
+pub struct Hex;
+
+#[cfg(not(test))]
+fn get_base_url() -> String {
+    format!("{REGISTRY_URL}/api/packages")
+}
+
+#[cfg(test)]
+fn get_base_url() -> String {
+    format!("{}/api/packages", &mockito::server_url())
+}
+
+impl Registry for Hex {
+    const NAME: &'static str = "hex";
+
+    fn get_latest_version<T: HttpClient>(
+        &self,
+        http_client: GenericHttpClient<T>,
+        pkg: &Package,
+    ) -> Result<Option<String>> {
+        let url = format!("{}/{}", get_base_url(), pkg);
+        let resp = http_client
+            .add_header("Accept", "application/json")
+            .get::<Response>(&url)?;
+
+        if !pkg.version().semver().pre.is_empty() {
+            return Ok(resp.latest_version);
+        }
+
+        Ok(resp.latest_stable_version)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{http_client, test_helper::mock_hex};
+    use std::time::Duration;
+
+    const PKG_NAME: &str = "jason";
+    const FIXTURES_PATH: &str = "tests/fixtures/registry/hex";
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    #[test]
+    fn failure_test() {
+        let pkg = Package::new("missing", "0.1.0").unwrap();
+        let client = http_client::new(http_client::DefaultHttpClient::default(), TIMEOUT);
+        let data_path = format!("{}/not_found.json", FIXTURES_PATH);
+        let _mock = mock_hex(&pkg, 404, &data_path);
+
+        let result = Hex.get_latest_version(client, &pkg);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn success_test() {
+        let pkg = Package::new(PKG_NAME, "1.4.0").unwrap();
+        let client = http_client::new(http_client::DefaultHttpClient::default(), TIMEOUT);
+        let data_path = format!("{}/package.json", FIXTURES_PATH);
+        let _mock = mock_hex(&pkg, 200, &data_path);
+
+        let result = Hex.get_latest_version(client, &pkg);
+
+        assert!(result.is_ok());
+        assert_eq!(result.expect("get result"), Some("1.4.1".to_string()));
+    }
+
+    #[test]
+    fn prerelease_test() {
+        let pkg = Package::new(PKG_NAME, "1.5.0-alpha.1").unwrap();
+        let client = http_client::new(http_client::DefaultHttpClient::default(), TIMEOUT);
+        let data_path = format!("{}/package.json", FIXTURES_PATH);
+        let _mock = mock_hex(&pkg, 200, &data_path);
+
+        let result = Hex.get_latest_version(client, &pkg);
+
+        assert!(result.is_ok());
+        assert_eq!(
+            result.expect("get result"),
+            Some("1.5.0-alpha.2".to_string())
+        );
+    }
+}
@@ -0,0 +1,315 @@
+use crate::{
+    http_client::{GenericHttpClient, HttpClient, HttpError},
+    Package, Registry, Result,
+};
+use serde::Deserialize;
+
+#[cfg(not(test))]
+const FORMULAE_URL: &str = "https://formulae.brew.sh";
+#[cfg(not(test))]
+const RAW_GITHUB_URL: &str = "https://raw.githubusercontent.com";
+
+/// The directories of a tap that may contain formulae, in the order Homebrew searches them.
+const TAP_FORMULA_DIRS: [&str; 3] = ["Formula/", "HomebrewFormula/", ""];
+
+#[derive(Deserialize)]
+struct Response {
+    versions: Versions,
+    #[serde(default)]
+    disabled: bool,
+}
+
+#[derive(Deserialize)]
+struct Versions {
+    stable: Option<String>,
+}
+
+/// The Homebrew package manager.
+///
+/// Formulae from `homebrew/core` are looked up using the `formulae.brew.sh` API, so only
+/// versions that `brew upgrade` can deliver are announced. Formulae from third-party taps
+/// are read from the formula file in the tap repository on GitHub, see [`Homebrew::tap`].
+///
+/// # Examples
+///
+/// ```rust
+/// use update_informer::{registry::Homebrew, Check};
+///
+/// let informer = update_informer::new(Homebrew::tap("owner/tap"), "formula", "0.1.0");
+/// let _ = informer.check_version();
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Homebrew {
+    tap: Option<String>,
+}
+
+impl Homebrew {
+    /// Constructs a new `Homebrew` registry for formulae from `homebrew/core`.
+    pub fn core() -> Self {
+        Self::default()
+    }
+
+    /// Constructs a new `Homebrew` registry for formulae from a third-party tap.
+    ///
+    /// # Arguments
+    ///
+    /// * `tap` - A tap name such as `owner/tap`, which refers to the
+    ///   `github.com/owner/homebrew-tap` repository. Formulae are looked up in its `Formula`
+    ///   and `HomebrewFormula` directories and at its root.
+    pub fn tap<T: Into<String>>(tap: T) -> Self {
+        Self {
+            tap: Some(tap.into()),
+        }
+    }
+}
+
+#[cfg(not(test))]
+fn get_formulae_url() -> String {
+    FORMULAE_URL.to_string()
+}
+
+#[cfg(test)]
+fn get_formulae_url() -> String {
+    mockito::server_url()
+}
+
+#[cfg(not(test))]
+fn get_raw_github_url() -> String {
+    RAW_GITHUB_URL.to_string()
+}
+
+#[cfg(test)]
+fn get_raw_github_url() -> String {
+    mockito::server_url()
+}
+
+impl Registry for Homebrew {
+    const NAME: &'static str = "homebrew";
+
+    fn get_latest_version<T: HttpClient>(
+        &self,
+        http_client: GenericHttpClient<T>,
+        pkg: &Package,
+    ) -> Result<Option<String>> {
+        let version = match &self.tap {
+            None => {
+                let url = format!("{}/api/formula/{}.json", get_formulae_url(), pkg);
+                let resp = http_client.get::<Response>(&url)?;
+
+                if resp.disabled {
+                    return Ok(None);
+                }
+
+                resp.versions.stable
+            }
+            Some(tap) => {
+                let (owner, repo) = tap.split_once('/').ok_or("tap must be `owner/tap`")?;
+                let repo = repo.strip_prefix("homebrew-").unwrap_or(repo);
+
+                let mut formula = Err(HttpError::Status(404).into());
+                for dir in TAP_FORMULA_DIRS {
+                    let url = format!(
+                        "{}/{owner}/homebrew-{repo}/HEAD/{dir}{pkg}.rb",
+                        get_raw_github_url()
+                    );
+
+                    formula = http_client.clone().get_text(&url);
+                    match &formula {
+                        Err(e) if e.downcast_ref() == Some(&HttpError::Status(404)) => continue,
+                        _ => break,
+                    }
+                }
+
+                formula_version(&formula?)
+            }
+        };
+
+        Ok(version.map(|v| normalize(&v)))
+    }
+}
+
+/// Extracts the version of a formula from an explicit `version "..."` stanza
+/// or from the file name of the first `url "..."`.
+fn formula_version(formula: &str) -> Option<String> {
+    let stanza = |name: &str| {
+        formula.lines().find_map(|line| {
+            let value = line.trim().strip_prefix(name)?.trim();
+            let value = value.strip_prefix('"')?;
+            value.split('"').next().map(str::to_string)
+        })
+    };
+
+    if let Some(version) = stanza("version ") {
+        return Some(version);
+    }
+
+    let url = stanza("url ")?;
+    let file_name = url.rsplit('/').next()?;
+
+    version_in(file_name)
+}
+
+/// Finds the first dotted number, e.g. `1.2.3` in `tool-v1.2.3-x86_64.tar.gz`.
+fn version_in(value: &str) -> Option<String> {
+    let bytes = value.as_bytes();
+    let mut start = 0;
+
+    while start < bytes.len() {
+        if !bytes[start].is_ascii_digit() {
+            start += 1;
+            continue;
+        }
+
+        let mut end = start;
+        while end < bytes.len()
+            && (bytes[end].is_ascii_digit()
+                || (bytes[end] == b'.' && bytes.get(end + 1).is_some_and(u8::is_ascii_digit)))
+        {
+            end += 1;
+        }
+
+        if value[start..end].contains('.') {
+            return Some(value[start..end].to_string());
+        }
+        start = end;
+    }
+
+    None
+}
+
+/// Pads versions such as `1.2` to `1.2.0` and strips leading zeros, e.g. `2024.03.01` becomes
+/// `2024.3.1`, since Homebrew versions are not always semantic versions.
+fn normalize(version: &str) -> String {
+    let is_numeric = version
+        .split('.')
+        .all(|part| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()));
+    if !is_numeric {
+        return version.to_string();
+    }
+
+    let mut parts: Vec<&str> = version
+        .split('.')
+        .map(|part| match part.trim_start_matches('0') {
+            "" => "0",
+            part => part,
+        })
+        .collect();
+    if parts.len() < 3 {
+        parts.resize(3, "0");
+    }
+
+    parts.join(".")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        http_client,
+        test_helper::{mock_homebrew, mock_http},
+    };
+    use std::{fs, time::Duration};
+
+    const FIXTURES_PATH: &str = "tests/fixtures/registry/homebrew";
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    #[test]
+    fn failure_test() {
+        let pkg = Package::new("missing", "0.1.0").unwrap();
+        let client = http_client::new(http_client::DefaultHttpClient::default(), TIMEOUT);
+        let data_path = format!("{}/not_found.html", FIXTURES_PATH);
+        let _mock = mock_homebrew(&pkg, 404, &data_path);
+
+        let result = Homebrew::core().get_latest_version(client, &pkg);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn core_test() {
+        let pkg = Package::new("git-cliff", "1.0.0").unwrap();
+        let client = http_client::new(http_client::DefaultHttpClient::default(), TIMEOUT);
+        let data_path = format!("{}/formula.json", FIXTURES_PATH);
+        let _mock = mock_homebrew(&pkg, 200, &data_path);
+
+        let result = Homebrew::core().get_latest_version(client, &pkg);
+
+        assert!(result.is_ok());
+        assert_eq!(result.expect("get result"), Some("2.4.0".to_string()));
+    }
+
+    #[test]
+    fn tap_test() {
+        let pkg = Package::new("tool", "1.0.0").unwrap();
+        let client = http_client::new(http_client::DefaultHttpClient::default(), TIMEOUT);
+        let data = fs::read_to_string(format!("{}/tool.rb", FIXTURES_PATH)).unwrap();
+        let _mock = mock_http("/owner/homebrew-tap/HEAD/Formula/tool.rb", 200, &data);
+
+        let result = Homebrew::tap("owner/tap").get_latest_version(client, &pkg);
+
+        assert!(result.is_ok());
+        assert_eq!(result.expect("get result"), Some("1.3.0".to_string()));
+    }
+
+    #[test]
+    fn tap_layouts_test() {
+        let pkg = Package::new("tool", "1.0.0").unwrap();
+        let client = http_client::new(http_client::DefaultHttpClient::default(), TIMEOUT);
+        let data = fs::read_to_string(format!("{}/tool.rb", FIXTURES_PATH)).unwrap();
+        let mut mocks = Vec::new();
+        for dir in [
+            "legacy/HEAD/Formula",
+            "root/HEAD/Formula",
+            "root/HEAD/HomebrewFormula",
+        ] {
+            let path = format!("/owner/homebrew-{dir}/tool.rb");
+            mocks.push(mock_http(&path, 404, "Not Found"));
+        }
+        for dir in TAP_FORMULA_DIRS {
+            let path = format!("/owner/homebrew-missing/HEAD/{dir}tool.rb");
+            mocks.push(mock_http(&path, 404, "Not Found"));
+        }
+        let _legacy = mock_http(
+            "/owner/homebrew-legacy/HEAD/HomebrewFormula/tool.rb",
+            200,
+            &data,
+        );
+        let _root = mock_http("/owner/homebrew-root/HEAD/tool.rb", 200, &data);
+
+        let result = Homebrew::tap("owner/legacy").get_latest_version(client.clone(), &pkg);
+        assert_eq!(result.expect("get result"), Some("1.3.0".to_string()));
+
+        let result = Homebrew::tap("owner/root").get_latest_version(client.clone(), &pkg);
+        assert_eq!(result.expect("get result"), Some("1.3.0".to_string()));
+
+        let error = Homebrew::tap("owner/missing")
+            .get_latest_version(client, &pkg)
+            .unwrap_err();
+        assert_eq!(error.downcast_ref(), Some(&HttpError::Status(404)));
+    }
+
+    #[test]
+    fn formula_version_test() {
+        let formula = r#"
+            class Tool < Formula
+              url "https://example.com/tool-2.0.tar.gz"
+              version "2.0.1"
+            end
+        "#;
+        assert_eq!(formula_version(formula), Some("2.0.1".to_string()));
+
+        let formula = r#"url "https://github.com/o/r/archive/refs/tags/v1.2.3.tar.gz""#;
+        assert_eq!(formula_version(formula), Some("1.2.3".to_string()));
+
+        assert_eq!(formula_version("class Tool < Formula\nend"), None);
+    }
+
+    #[test]
+    fn normalize_test() {
+        assert_eq!(normalize("1"), "1.0.0");
+        assert_eq!(normalize("1.2"), "1.2.0");
+        assert_eq!(normalize("1.2.3"), "1.2.3");
+        assert_eq!(normalize("1.2-beta"), "1.2-beta");
+        assert_eq!(normalize("2024.03.01"), "2024.3.1");
+        assert_eq!(normalize("1.0"), "1.0.0");
+    }
+}
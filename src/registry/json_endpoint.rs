@@ -0,0 +1,221 @@
+use crate::{
+    http_client::{GenericHttpClient, HttpClient},
+    registry::cache_name,
+    Package, Registry, Result,
+};
+use serde_json::Value;
+
+/// A registry reading the latest version from a JSON response of any HTTP endpoint.
+///
+/// The URL is a template in which `{owner}` and `{name}` are replaced with the parts of the
+/// package name, e.g. `owner` and `repo` for `owner/repo`. The version is located in the
+/// response using a JSON Pointer such as `/data/latest/version`, or a path such as
+/// `data.latest.version` or `releases.0.version`. A `null` value means no version is available.
+///
+/// # Examples
+///
+/// ```rust
+/// use update_informer::{registry::JsonEndpoint, Check};
+///
+/// let registry = JsonEndpoint::new("https://turbo.build/api/binaries/version", "version")
+///     .header("Accept", "application/json");
+///
+/// let informer = update_informer::new(registry, "turbo", "0.1.0");
+/// let _ = informer.check_version();
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JsonEndpoint {
+    url: String,
+    pointer: String,
+    headers: Vec<(String, String)>,
+}
+
+impl JsonEndpoint {
+    /// Constructs a new `JsonEndpoint`.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - A URL template, e.g. `https://example.com/api/{owner}/{name}/latest`.
+    /// * `path` - A JSON Pointer (starting with `/`) or a dot-separated path to the version.
+    pub fn new<U: Into<String>, P: AsRef<str>>(url: U, path: P) -> Self {
+        Self {
+            url: url.into(),
+            pointer: to_pointer(path.as_ref()),
+            headers: Vec::new(),
+        }
+    }
+
+    /// Adds a header sent with the request, e.g. for authentication.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - A header name.
+    /// * `value` - A header value.
+    pub fn header<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.headers.push((key.into(), value.into()));
+        self
+    }
+
+    fn url(&self, pkg: &Package) -> Result<String> {
+        let full_name = pkg.to_string();
+        let (owner, name) = match full_name.split_once('/') {
+            Some((owner, name)) => (Some(owner), name),
+            None => (None, full_name.as_str()),
+        };
+
+        let url = self.url.replace("{name}", name);
+        if !url.contains("{owner}") {
+            return Ok(url);
+        }
+
+        let owner = owner.ok_or("the URL requires a package name in the form `owner/name`")?;
+        Ok(url.replace("{owner}", owner))
+    }
+}
+
+impl Registry for JsonEndpoint {
+    const NAME: &'static str = "json";
+
+    /// Returns e.g. `json-example.com-1a2b3c4d`, identifying the URL and the path to the version.
+    fn cache_name(&self) -> String {
+        cache_name::with_url(Self::NAME, &self.url, &self.pointer)
+    }
+
+    fn get_latest_version<T: HttpClient>(
+        &self,
+        http_client: GenericHttpClient<T>,
+        pkg: &Package,
+    ) -> Result<Option<String>> {
+        let url = self.url(pkg)?;
+
+        let mut http_client = http_client;
+        for (key, value) in &self.headers {
+            http_client = http_client.add_header(key, value);
+        }
+
+        let resp = http_client.get::<Value>(&url)?;
+
+        match resp.pointer(&self.pointer) {
+            Some(Value::String(version)) => Ok(Some(version.clone())),
+            Some(Value::Number(version)) => Ok(Some(version.to_string())),
+            Some(Value::Null) => Ok(None),
+            Some(_) => Err(format!("value at `{}` is not a version", self.pointer).into()),
+            None => Err(format!("no value at `{}` in the response", self.pointer).into()),
+        }
+    }
+}
+
+/// Converts a dot-separated path into a JSON Pointer, e.g. `data.version` into `/data/version`.
+fn to_pointer(path: &str) -> String {
+    if path.is_empty() || path.starts_with('/') {
+        return path.to_string();
+    }
+
+    path.split('.')
+        .map(|segment| format!("/{}", segment.replace('~', "~0").replace('/', "~1")))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{http_client, test_helper::mock_http};
+    use mockito::mock;
+    use std::time::Duration;
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    fn registry(path: &str) -> JsonEndpoint {
+        JsonEndpoint::new(
+            format!("{}/api/{{owner}}/{{name}}", mockito::server_url()),
+            path,
+        )
+    }
+
+    #[test]
+    fn pointer_test() {
+        let pkg = Package::new("owner/tool", "1.0.0").unwrap();
+        let client = http_client::new(http_client::DefaultHttpClient::default(), TIMEOUT);
+        let _mock = mock_http(
+            "/api/owner/tool",
+            200,
+            r#"{"data":{"releases":[{"version":"1.2.0"}]}}"#,
+        );
+
+        let result = registry("/data/releases/0/version").get_latest_version(client, &pkg);
+
+        assert_eq!(result.expect("get result"), Some("1.2.0".to_string()));
+    }
+
+    #[test]
+    fn path_with_headers_test() {
+        let pkg = Package::new("owner/tool", "1.0.0").unwrap();
+        let client = http_client::new(http_client::DefaultHttpClient::default(), TIMEOUT);
+        let _mock = mock("GET", "/api/owner/tool")
+            .match_header("Authorization", "Bearer secret")
+            .with_status(200)
+            .with_body(r#"{"data":{"latest":{"version":"2.0.1"}}}"#)
+            .create();
+
+        let result = registry("data.latest.version")
+            .header("Authorization", "Bearer secret")
+            .get_latest_version(client, &pkg);
+
+        assert_eq!(result.expect("get result"), Some("2.0.1".to_string()));
+    }
+
+    #[test]
+    fn null_test() {
+        let pkg = Package::new("owner/unreleased", "1.0.0").unwrap();
+        let client = http_client::new(http_client::DefaultHttpClient::default(), TIMEOUT);
+        let _mock = mock_http("/api/owner/unreleased", 200, r#"{"version":null}"#);
+
+        let result = registry("version").get_latest_version(client, &pkg);
+
+        assert_eq!(result.expect("get result"), None);
+    }
+
+    #[test]
+    fn missing_value_test() {
+        let pkg = Package::new("owner/other", "1.0.0").unwrap();
+        let client = http_client::new(http_client::DefaultHttpClient::default(), TIMEOUT);
+        let _mock = mock_http("/api/owner/other", 200, r#"{"name":"other"}"#);
+
+        let result = registry("version").get_latest_version(client, &pkg);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn url_without_owner_test() {
+        let pkg = Package::new("tool", "1.0.0").unwrap();
+
+        assert!(registry("version").url(&pkg).is_err());
+
+        let registry = JsonEndpoint::new("https://example.com/{name}.json", "version");
+        assert_eq!(registry.url(&pkg).unwrap(), "https://example.com/tool.json");
+    }
+
+    #[test]
+    fn cache_name_test() {
+        let registry = JsonEndpoint::new("https://example.com/api/{name}", "version");
+        let cache_name = registry.cache_name();
+        assert!(cache_name.starts_with("json-example.com-"), "{cache_name}");
+        assert_eq!(cache_name, registry.clone().cache_name());
+
+        let other = JsonEndpoint::new("https://example.com/other/{name}", "version");
+        assert_ne!(other.cache_name(), cache_name);
+
+        let beta = JsonEndpoint::new("https://example.com/api/{name}", "beta.version");
+        assert_ne!(beta.cache_name(), cache_name);
+    }
+
+    #[test]
+    fn to_pointer_test() {
+        assert_eq!(to_pointer("version"), "/version");
+        assert_eq!(to_pointer("releases.0.version"), "/releases/0/version");
+        assert_eq!(to_pointer("a/b.c~d"), "/a~1b/c~0d");
+        assert_eq!(to_pointer("/data/version"), "/data/version");
+        assert_eq!(to_pointer(""), "");
+    }
+}
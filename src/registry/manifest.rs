@@ -0,0 +1,502 @@
+use crate::{
+    http_client::{GenericHttpClient, HttpClient},
+    registry::cache_name,
+    Package, Registry, Release, Result, Version,
+};
+use serde::Deserialize;
+use std::{
+    collections::HashMap,
+    env::consts::{ARCH, OS},
+};
+
+const DEFAULT_CHANNEL: &str = "stable";
+
+#[derive(Deserialize)]
+struct Response {
+    releases: Vec<Entry>,
+}
+
+#[derive(Deserialize)]
+struct Entry {
+    version: String,
+    channel: Option<String>,
+    min_supported_version: Option<String>,
+    url: Option<String>,
+    notes_url: Option<String>,
+    #[serde(default)]
+    platforms: HashMap<String, String>,
+}
+
+/// A static update manifest hosted on your own server, e.g. on a CDN.
+///
+/// The manifest is either a JSON file with the following schema or a Sparkle appcast feed:
+///
+/// ```json
+/// {
+///   "releases": [
+///     {
+///       "version": "1.4.0",
+///       "channel": "stable",
+///       "min_supported_version": "1.0.0",
+///       "notes_url": "https://example.com/changelog#1.4.0",
+///       "url": "https://example.com/download",
+///       "platforms": {
+///         "linux-x86_64": "https://example.com/tool-1.4.0-linux-x86_64.tar.gz",
+///         "macos-aarch64": "https://example.com/tool-1.4.0-macos-aarch64.tar.gz"
+///       }
+///     }
+///   ]
+/// }
+/// ```
+///
+/// Only `version` is required. Releases without a channel belong to the `stable` channel,
+/// which is also available on every other channel. Releases with `platforms` are only
+/// available on the listed targets (`{os}-{arch}`, as in `std::env::consts`), and their
+/// platform URL takes precedence over `url`.
+///
+/// In an appcast feed, the version is read from `sparkle:shortVersionString` (or `sparkle:version`),
+/// the channel from `sparkle:channel`, the download URL and OS from the `enclosure`, the release
+/// notes from `sparkle:releaseNotesLink`, and the minimum supported version from `sparkle:criticalUpdate`
+/// unless its version is a build number.
+///
+/// # Examples
+///
+/// ```rust
+/// use update_informer::{registry::Manifest, Check};
+///
+/// let registry = Manifest::new("https://example.com/updates.json").channel("beta");
+///
+/// let informer = update_informer::new(registry, "tool", "0.1.0");
+/// if let Ok(Some(update)) = informer.check_update() {
+///     println!("Download: {:?}", update.release().url);
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Manifest {
+    url: String,
+    channel: String,
+    target: String,
+}
+
+impl Manifest {
+    /// Constructs a new `Manifest` for the `stable` channel and the current target.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - A URL of the JSON manifest or the appcast feed.
+    pub fn new<U: Into<String>>(url: U) -> Self {
+        Self {
+            url: url.into(),
+            channel: DEFAULT_CHANNEL.to_string(),
+            target: format!("{OS}-{ARCH}"),
+        }
+    }
+
+    /// Sets the release channel, e.g. `beta`.
+    pub fn channel<C: Into<String>>(self, channel: C) -> Self {
+        Self {
+            channel: channel.into(),
+            ..self
+        }
+    }
+
+    /// Sets the target used to select a platform, e.g. `linux-x86_64`.
+    pub fn target<T: Into<String>>(self, target: T) -> Self {
+        Self {
+            target: target.into(),
+            ..self
+        }
+    }
+
+    fn is_available(&self, channel: Option<&str>) -> bool {
+        let channel = channel.unwrap_or(DEFAULT_CHANNEL);
+        channel == DEFAULT_CHANNEL || channel == self.channel
+    }
+
+    fn json_releases(&self, manifest: &str) -> Result<Vec<Release>> {
+        let resp: Response = serde_json::from_str(manifest)?;
+
+        let releases = resp
+            .releases
+            .into_iter()
+            .filter(|entry| self.is_available(entry.channel.as_deref()))
+            .filter_map(|mut entry| {
+                let url = if entry.platforms.is_empty() {
+                    entry.url
+                } else {
+                    Some(entry.platforms.remove(&self.target)?)
+                };
+
+                Some(Release {
+                    url,
+                    min_supported_version: entry.min_supported_version,
+                    notes_url: entry.notes_url,
+                    ..Release::new(entry.version)
+                })
+            })
+            .collect();
+
+        Ok(releases)
+    }
+
+    fn appcast_releases(&self, feed: &str) -> Vec<Release> {
+        let os = self.target.split('-').next().unwrap_or_default();
+
+        feed.split("<item>")
+            .skip(1)
+            .filter_map(|item| {
+                let item = item.split("</item>").next()?;
+                if !self.is_available(appcast::element(item, "sparkle:channel").as_deref()) {
+                    return None;
+                }
+
+                let enclosure = appcast::tag(item, "enclosure");
+                let attribute = |name| enclosure.and_then(|tag| appcast::attribute(tag, name));
+
+                if attribute("sparkle:os").is_some_and(|enclosure_os| enclosure_os != os) {
+                    return None;
+                }
+
+                let version = appcast::element(item, "sparkle:shortVersionString")
+                    .or_else(|| attribute("sparkle:shortVersionString"))
+                    .or_else(|| appcast::element(item, "sparkle:version"))
+                    .or_else(|| attribute("sparkle:version"))?;
+
+                // A critical update is required for all versions, or only for versions below
+                // the given one. That is a build number such as `200` in most feeds, which cannot
+                // be compared to the short version string, so the update is not required then.
+                let min_supported_version =
+                    appcast::tag(item, "sparkle:criticalUpdate").and_then(|tag| {
+                        match appcast::attribute(tag, "sparkle:version") {
+                            Some(min) => Version::parse(&min).is_ok().then_some(min),
+                            None => Some(version.clone()),
+                        }
+                    });
+
+                Some(Release {
+                    url: attribute("url"),
+                    min_supported_version,
+                    notes_url: appcast::element(item, "sparkle:releaseNotesLink"),
+                    ..Release::new(version)
+                })
+            })
+            .collect()
+    }
+}
+
+impl Registry for Manifest {
+    const NAME: &'static str = "manifest";
+
+    fn cache_name(&self) -> String {
+        let settings = format!("{}\n{}", self.channel, self.target);
+        cache_name::with_url(Self::NAME, &self.url, &settings)
+    }
+
+    fn get_latest_version<T: HttpClient>(
+        &self,
+        http_client: GenericHttpClient<T>,
+        pkg: &Package,
+    ) -> Result<Option<String>> {
+        let release = self.get_latest_release(http_client, pkg)?;
+
+        Ok(release.map(|r| r.version))
+    }
+
+    fn get_latest_release<T: HttpClient>(
+        &self,
+        http_client: GenericHttpClient<T>,
+        _pkg: &Package,
+    ) -> Result<Option<Release>> {
+        let manifest = http_client.get_text(&self.url)?;
+
+        let releases = if manifest.trim_start().starts_with('<') {
+            self.appcast_releases(&manifest)
+        } else {
+            self.json_releases(&manifest)?
+        };
+
+        let latest = releases
+            .into_iter()
+            .filter_map(|r| Version::parse(&r.version).ok().map(|v| (v, r)))
+            .max_by(|(a, _), (b, _)| a.cmp(b))
+            .map(|(_, release)| release);
+
+        Ok(latest)
+    }
+}
+
+/// A minimal reader for the elements of an appcast feed.
+mod appcast {
+    const CDATA_START: &str = "<![CDATA[";
+    const CDATA_END: &str = "]]>";
+
+    /// Returns the trimmed text of the first element with the given name, without
+    /// entities and CDATA sections.
+    pub(super) fn element(xml: &str, name: &str) -> Option<String> {
+        let start = xml.find(&format!("<{name}>"))? + name.len() + 2;
+        let end = start + xml[start..].find(&format!("</{name}>"))?;
+
+        Some(text(&xml[start..end]).trim().to_string()).filter(|text| !text.is_empty())
+    }
+
+    /// Returns the opening tag of the first element with the given name, e.g. `<enclosure url="..." />`.
+    pub(super) fn tag<'a>(xml: &'a str, name: &str) -> Option<&'a str> {
+        let pattern = format!("<{name}");
+        let mut offset = 0;
+
+        while let Some(pos) = xml[offset..].find(&pattern) {
+            let start = offset + pos;
+            let rest = &xml[start + pattern.len()..];
+
+            // Skip elements whose name only starts with the given name
+            if rest.starts_with(|c: char| c.is_whitespace() || c == '/' || c == '>') {
+                let end = start + xml[start..].find('>')?;
+                return Some(&xml[start..=end]);
+            }
+            offset = start + pattern.len();
+        }
+
+        None
+    }
+
+    /// Returns the value of an attribute of an opening tag, without entities.
+    pub(super) fn attribute(tag: &str, name: &str) -> Option<String> {
+        let pattern = format!("{name}=\"");
+
+        tag.match_indices(&pattern)
+            .find(|(pos, _)| tag[..*pos].ends_with(char::is_whitespace))
+            .and_then(|(pos, _)| {
+                let start = pos + pattern.len();
+                let end = start + tag[start..].find('"')?;
+                Some(unescape(&tag[start..end]))
+            })
+    }
+
+    /// Returns the contents of CDATA sections as they are and unescapes the rest.
+    fn text(raw: &str) -> String {
+        let mut text = String::with_capacity(raw.len());
+        let mut rest = raw;
+
+        while let Some(start) = rest.find(CDATA_START) {
+            text.push_str(&unescape(&rest[..start]));
+
+            let cdata = &rest[start + CDATA_START.len()..];
+            let end = cdata.find(CDATA_END).unwrap_or(cdata.len());
+            text.push_str(&cdata[..end]);
+            rest = cdata.get(end + CDATA_END.len()..).unwrap_or_default();
+        }
+
+        text.push_str(&unescape(rest));
+        text
+    }
+
+    /// Replaces the predefined entities, e.g. `&amp;` with `&`. Other references are kept.
+    pub(super) fn unescape(escaped: &str) -> String {
+        const ENTITIES: [(&str, char); 5] = [
+            ("&amp;", '&'),
+            ("&lt;", '<'),
+            ("&gt;", '>'),
+            ("&quot;", '"'),
+            ("&apos;", '\''),
+        ];
+
+        let mut text = String::with_capacity(escaped.len());
+        let mut rest = escaped;
+
+        while let Some(pos) = rest.find('&') {
+            text.push_str(&rest[..pos]);
+            rest = &rest[pos..];
+
+            match ENTITIES.iter().find(|(entity, _)| rest.starts_with(entity)) {
+                Some((entity, c)) => {
+                    text.push(*c);
+                    rest = &rest[entity.len()..];
+                }
+                None => {
+                    text.push('&');
+                    rest = &rest[1..];
+                }
+            }
+        }
+
+        text.push_str(rest);
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{http_client, test_helper::mock_http};
+    use std::{fs, time::Duration};
+
+    const FIXTURES_PATH: &str = "tests/fixtures/registry/manifest";
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    fn manifest(file_name: &str) -> Manifest {
+        Manifest::new(format!("{}/{}", mockito::server_url(), file_name))
+    }
+
+    fn get_latest_release(registry: &Manifest, file_name: &str) -> Result<Option<Release>> {
+        let pkg = Package::new("tool", "1.0.0").unwrap();
+        let client = http_client::new(http_client::DefaultHttpClient::default(), TIMEOUT);
+        let data = fs::read_to_string(format!("{}/{}", FIXTURES_PATH, file_name)).unwrap();
+        let _mock = mock_http(&format!("/{file_name}"), 200, &data);
+
+        registry.get_latest_release(client, &pkg)
+    }
+
+    #[test]
+    fn cache_name_test() {
+        let registry = Manifest::new("https://example.com/updates.json").target("linux-x86_64");
+        let cache_name = registry.cache_name();
+        assert!(
+            cache_name.starts_with("manifest-example.com-"),
+            "{cache_name}"
+        );
+        assert_eq!(cache_name, registry.clone().cache_name());
+
+        let other = Manifest::new("https://example.com/other.json").target("linux-x86_64");
+        assert_ne!(other.cache_name(), cache_name);
+
+        let beta = registry.clone().channel("beta");
+        assert_ne!(beta.cache_name(), cache_name);
+
+        let macos = registry.target("macos-aarch64");
+        assert_ne!(macos.cache_name(), cache_name);
+    }
+
+    #[test]
+    fn failure_test() {
+        let pkg = Package::new("tool", "1.0.0").unwrap();
+        let client = http_client::new(http_client::DefaultHttpClient::default(), TIMEOUT);
+        let _mock = mock_http("/missing.json", 404, "Not Found");
+
+        let result = manifest("missing.json").get_latest_release(client, &pkg);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn json_stable_test() {
+        let registry = manifest("updates.json").target("linux-x86_64");
+        let release = get_latest_release(&registry, "updates.json").expect("get result");
+
+        let expected = Release {
+            min_supported_version: Some("1.0.0".to_string()),
+            url: Some("https://example.com/tool-1.4.0-linux-x86_64.tar.gz".to_string()),
+            notes_url: Some("https://example.com/changelog#1.4.0".to_string()),
+            ..Release::new("1.4.0")
+        };
+        assert_eq!(release, Some(expected));
+    }
+
+    #[test]
+    fn json_channel_and_target_test() {
+        let registry = manifest("updates.json")
+            .channel("beta")
+            .target("windows-x86_64");
+        let release = get_latest_release(&registry, "updates.json").expect("get result");
+
+        assert_eq!(release.map(|r| r.version), Some("1.5.0-beta.1".to_string()));
+
+        // 1.4.0 is not available for this target, so 1.3.0 is the latest stable release
+        let registry = manifest("updates.json").target("windows-x86_64");
+        let release = get_latest_release(&registry, "updates.json").expect("get result");
+
+        let expected = Release {
+            url: Some("https://example.com/download".to_string()),
+            ..Release::new("1.3.0")
+        };
+        assert_eq!(release, Some(expected));
+    }
+
+    #[test]
+    fn appcast_test() {
+        let registry = manifest("appcast.xml").target("macos-aarch64");
+        let release = get_latest_release(&registry, "appcast.xml").expect("get result");
+
+        let expected = Release {
+            min_supported_version: Some("2.0.0".to_string()),
+            url: Some(
+                "https://example.com/download?file=Tool-2.1.0.zip&arch=universal".to_string(),
+            ),
+            notes_url: Some("https://example.com/notes/2.1.0.html?lang=en&ref=appcast".to_string()),
+            ..Release::new("2.1.0")
+        };
+        assert_eq!(release, Some(expected));
+
+        let registry = manifest("appcast.xml")
+            .channel("beta")
+            .target("macos-aarch64");
+        let release = get_latest_release(&registry, "appcast.xml").expect("get result");
+        assert_eq!(release.map(|r| r.version), Some("2.2.0-beta.1".to_string()));
+    }
+
+    #[test]
+    fn appcast_critical_update_test() {
+        let feed = r#"
+            <item>
+              <sparkle:version>300</sparkle:version>
+              <sparkle:shortVersionString>3.0.0</sparkle:shortVersionString>
+              <sparkle:criticalUpdate sparkle:version="200" />
+            </item>
+            <item>
+              <sparkle:shortVersionString>2.0.0</sparkle:shortVersionString>
+              <sparkle:criticalUpdate />
+            </item>
+        "#;
+        let releases = manifest("appcast.xml").appcast_releases(feed);
+
+        let min_versions: Vec<_> = releases
+            .iter()
+            .map(|r| r.min_supported_version.as_deref())
+            .collect();
+        assert_eq!(min_versions, [None, Some("2.0.0")]);
+
+        // An unparseable minimum supported version does not turn the update into an error
+        let pkg = Package::new("tool", "1.0.0").unwrap();
+        let release = Release {
+            min_supported_version: Some("200".to_string()),
+            ..Release::new("3.0.0")
+        };
+        let update = crate::Update::check(&pkg, release, crate::VersionScheme::Semver)
+            .expect("check update")
+            .expect("get update");
+        assert!(!update.is_required());
+    }
+
+    #[test]
+    fn appcast_attribute_test() {
+        let tag = r#"<enclosure url="https://example.com/a.zip" sparkle:version="12" length="1"/>"#;
+
+        assert_eq!(
+            appcast::tag(&format!("<item>{tag}</item>"), "enclosure"),
+            Some(tag)
+        );
+        assert_eq!(
+            appcast::attribute(tag, "url").as_deref(),
+            Some("https://example.com/a.zip")
+        );
+        assert_eq!(appcast::attribute(tag, "version"), None);
+        assert_eq!(
+            appcast::attribute(tag, "sparkle:version").as_deref(),
+            Some("12")
+        );
+    }
+
+    #[test]
+    fn appcast_text_test() {
+        let element = |xml: &str| appcast::element(xml, "link");
+
+        assert_eq!(
+            element("<link>a?b=1&amp;c=&lt;2&gt;&quot;&apos;</link>").as_deref(),
+            Some("a?b=1&c=<2>\"'")
+        );
+        assert_eq!(
+            element("<link> <![CDATA[ a?b=1&amp;c=2 ]]> </link>").as_deref(),
+            Some("a?b=1&amp;c=2")
+        );
+        assert_eq!(element("<link><![CDATA[]]></link>"), None);
+        assert_eq!(appcast::unescape("&amp;amp; &copy; &"), "&amp; &copy; &");
+    }
+}
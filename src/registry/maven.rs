@@ -0,0 +1,166 @@
+use crate::{
+    http_client::{GenericHttpClient, HttpClient},
+    Package, Registry, Result, Version,
+};
+
+#[cfg(not(test))]
+const REGISTRY_URL: &str = "https://repo1.maven.org";
+
+/// The Maven Central repository.
+///
+/// Artifacts are named using Maven coordinates, e.g. `com.example:tool`. The highest version
+/// listed in `maven-metadata.xml` is used, skipping versions that are not semantic versions,
+/// e.g. `31.1-jre` or `1.0.RC2`. Pre-releases are only considered if the current version
+/// is a pre-release.
+pub struct Maven;
+
+#[cfg(not(test))]
+fn get_base_url() -> String {
+    format!("{REGISTRY_URL}/maven2")
+}
+
+#[cfg(test)]
+fn get_base_url() -> String {
+    format!("{}/maven2", &mockito::server_url())
+}
+
+impl Registry for Maven {
+    const NAME: &'static str = "maven";
+
+    fn get_latest_version<T: HttpClient>(
+        &self,
+        http_client: GenericHttpClient<T>,
+        pkg: &Package,
+    ) -> Result<Option<String>> {
+        let url = format!(
+            "{}/{}/maven-metadata.xml",
+            get_base_url(),
+            metadata_path(pkg)?
+        );
+        let metadata = http_client.get_text(&url)?;
+
+        let prereleases = !pkg.version().semver().pre.is_empty();
+
+        Ok(latest_version(&metadata, prereleases))
+    }
+}
+
+/// Returns the highest listed version that can be parsed.
+///
+/// The `release` element is not used, since it is often a release candidate.
+fn latest_version(metadata: &str, prereleases: bool) -> Option<String> {
+    let versions = element(metadata, "versions")?;
+    let mut latest: Option<(Version, &str)> = None;
+
+    for raw in versions.split("<version>").skip(1) {
+        let Some((raw, _)) = raw.split_once("</version>") else {
+            continue;
+        };
+        let raw = raw.trim();
+
+        let Ok(version) = Version::parse(raw) else {
+            continue;
+        };
+        if !prereleases && !version.semver().pre.is_empty() {
+            continue;
+        }
+
+        if latest.as_ref().is_none_or(|(v, _)| &version > v) {
+            latest = Some((version, raw));
+        }
+    }
+
+    latest.map(|(_, raw)| raw.to_string())
+}
+
+/// Returns the path of an artifact, e.g. `com/example/tool` for `com.example:tool`.
+fn metadata_path(pkg: &Package) -> Result<String> {
+    let coordinates = pkg.to_string();
+    let (group_id, artifact_id) = coordinates
+        .split_once(':')
+        .ok_or("artifact must be `group_id:artifact_id`")?;
+
+    Ok(format!("{}/{artifact_id}", group_id.replace('.', "/")))
+}
+
+/// Returns the trimmed text of the first element with the given name.
+fn element<'a>(xml: &'a str, name: &str) -> Option<&'a str> {
+    let start = xml.find(&format!("<{name}>"))? + name.len() + 2;
+    let end = start + xml[start..].find(&format!("</{name}>"))?;
+
+    Some(xml[start..end].trim()).filter(|text| !text.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{http_client, test_helper::mock_maven};
+    use std::{fs, time::Duration};
+
+    const PKG_NAME: &str = "com.example:tool";
+    const FIXTURES_PATH: &str = "tests/fixtures/registry/maven";
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    #[test]
+    fn failure_test() {
+        let pkg = Package::new("com.example:missing", "0.1.0").unwrap();
+        let client = http_client::new(http_client::DefaultHttpClient::default(), TIMEOUT);
+        let data_path = format!("{}/not_found.html", FIXTURES_PATH);
+        let _mock = mock_maven("com/example/missing", 404, &data_path);
+
+        let result = Maven.get_latest_version(client, &pkg);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn success_test() {
+        let pkg = Package::new(PKG_NAME, "1.4.0").unwrap();
+        let client = http_client::new(http_client::DefaultHttpClient::default(), TIMEOUT);
+        let data_path = format!("{}/maven-metadata.xml", FIXTURES_PATH);
+        let _mock = mock_maven("com/example/tool", 200, &data_path);
+
+        let result = Maven.get_latest_version(client, &pkg);
+
+        assert!(result.is_ok());
+        assert_eq!(result.expect("get result"), Some("1.4.2".to_string()));
+    }
+
+    #[test]
+    fn prerelease_test() {
+        let pkg = Package::new(PKG_NAME, "2.0.0-beta.1").unwrap();
+        let client = http_client::new(http_client::DefaultHttpClient::default(), TIMEOUT);
+        let data_path = format!("{}/maven-metadata.xml", FIXTURES_PATH);
+        let _mock = mock_maven("com/example/tool", 200, &data_path);
+
+        let result = Maven.get_latest_version(client, &pkg);
+
+        assert_eq!(result.expect("get result"), Some("2.0.0-RC1".to_string()));
+    }
+
+    #[test]
+    fn latest_version_test() {
+        let xml = fs::read_to_string(format!("{}/mixed-versions.xml", FIXTURES_PATH)).unwrap();
+
+        assert_eq!(latest_version(&xml, false), Some("2.0.1".to_string()));
+        assert_eq!(latest_version(&xml, true), Some("2.1.0-RC1".to_string()));
+        assert_eq!(latest_version("<metadata></metadata>", false), None);
+    }
+
+    #[test]
+    fn element_test() {
+        let xml = fs::read_to_string(format!("{}/maven-metadata.xml", FIXTURES_PATH)).unwrap();
+
+        assert_eq!(element(&xml, "release"), Some("1.4.2"));
+        assert_eq!(element(&xml, "latest"), Some("2.0.0-RC1"));
+        assert_eq!(element(&xml, "missing"), None);
+    }
+
+    #[test]
+    fn metadata_path_test() {
+        let pkg = Package::new(PKG_NAME, "1.0.0").unwrap();
+        assert_eq!(metadata_path(&pkg).unwrap(), "com/example/tool");
+
+        let pkg = Package::new("tool", "1.0.0").unwrap();
+        assert!(metadata_path(&pkg).is_err());
+    }
+}
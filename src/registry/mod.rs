@@ -1,28 +1,98 @@
 use crate::{
     http_client::{GenericHttpClient, HttpClient},
-    Package, Result,
+    Package, Release, Result, VersionScheme,
 };
 
+mod cache_name;
+
 #[cfg(feature = "crates")]
 mod crates;
 #[cfg(feature = "crates")]
 pub use crates::Crates;
 
+#[cfg(feature = "crates")]
+mod cargo;
+#[cfg(feature = "crates")]
+pub use cargo::CargoRegistry;
+
+#[cfg(feature = "crates")]
+mod crates_index;
+#[cfg(all(feature = "crates", test))]
+pub(crate) use crates_index::index_path;
+#[cfg(feature = "crates")]
+pub use crates_index::CratesIndex;
+
+mod fallback;
+pub use fallback::{Fallback, RegistryList};
+
 #[cfg(feature = "github")]
 mod github;
 #[cfg(feature = "github")]
 pub use github::GitHub;
 
+#[cfg(feature = "goproxy")]
+mod goproxy;
+#[cfg(feature = "goproxy")]
+pub use goproxy::GoProxy;
+
+#[cfg(feature = "hex")]
+mod hex;
+#[cfg(feature = "hex")]
+pub use hex::Hex;
+
+#[cfg(feature = "homebrew")]
+mod homebrew;
+#[cfg(feature = "homebrew")]
+pub use homebrew::Homebrew;
+
+#[cfg(feature = "json-endpoint")]
+mod json_endpoint;
+#[cfg(feature = "json-endpoint")]
+pub use json_endpoint::JsonEndpoint;
+
+#[cfg(feature = "manifest")]
+mod manifest;
+#[cfg(feature = "manifest")]
+pub use manifest::Manifest;
+
+#[cfg(feature = "maven")]
+mod maven;
+#[cfg(feature = "maven")]
+pub use maven::Maven;
+
+mod multi;
+pub use multi::MultiRegistry;
+
 #[cfg(feature = "npm")]
 mod npm;
 #[cfg(feature = "npm")]
-pub use npm::Npm;
+pub use npm::{Npm, NpmRegistry};
+
+#[cfg(feature = "nuget")]
+mod nuget;
+#[cfg(feature = "nuget")]
+pub use nuget::NuGet;
+
+#[cfg(feature = "oci")]
+mod oci;
+#[cfg(feature = "oci")]
+pub use oci::Oci;
+
+#[cfg(feature = "packagist")]
+mod packagist;
+#[cfg(feature = "packagist")]
+pub use packagist::Packagist;
 
 #[cfg(feature = "pypi")]
 mod pypi;
 
 #[cfg(feature = "pypi")]
-pub use pypi::PyPI;
+pub use pypi::{PyPI, PyPIIndex};
+
+#[cfg(feature = "rubygems")]
+mod rubygems;
+#[cfg(feature = "rubygems")]
+pub use rubygems::RubyGems;
 
 pub trait Registry {
     /// The name of the registry.
@@ -36,7 +106,43 @@ pub trait Registry {
     /// * `pkg` - A `Package` struct.
     /// * `current_version` - The current version of the package.
     fn get_latest_version<T: HttpClient>(
+        &self,
         http_client: GenericHttpClient<T>,
         pkg: &Package,
     ) -> Result<Option<String>>;
+
+    /// Gets the latest release of a package from the registry.
+    ///
+    /// By default, the release only contains the version returned by [`get_latest_version`](Self::get_latest_version).
+    /// Registries that know more about a release, e.g. its download URL, can override this method.
+    ///
+    /// # Arguments
+    ///
+    /// * `http_client` - An HTTP client to send requests to the registry.
+    /// * `pkg` - A `Package` struct.
+    fn get_latest_release<T: HttpClient>(
+        &self,
+        http_client: GenericHttpClient<T>,
+        pkg: &Package,
+    ) -> Result<Option<Release>> {
+        let version = self.get_latest_version(http_client, pkg)?;
+
+        Ok(version.map(Release::new))
+    }
+
+    /// Returns the name used in the keys of the cache, e.g. `crates` in `crates-repo`.
+    ///
+    /// [`NAME`](Self::NAME) by default. Registries configured with a URL should include it,
+    /// so that registries with different URLs do not share the cached versions of packages
+    /// with the same name.
+    fn cache_name(&self) -> String {
+        Self::NAME.to_string()
+    }
+
+    /// Returns the versioning scheme used to parse and compare versions of the registry.
+    ///
+    /// Semantic Versioning by default.
+    fn version_scheme(&self) -> VersionScheme {
+        VersionScheme::Semver
+    }
 }
@@ -0,0 +1,401 @@
+use crate::{
+    http_client::{GenericHttpClient, HttpClient},
+    registry::{cache_name, fallback::with_source, RegistryList},
+    Package, Registry, Release, Result, Version, VersionScheme,
+};
+use std::{env, path::Path};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Mode {
+    All,
+    Installed(Option<String>),
+}
+
+/// A registry that checks a package published to several registries, e.g. crates.io, npm and PyPI.
+///
+/// Releases propagate to registries at different speeds, so a version is only reported once it
+/// is available where it matters:
+///
+/// * [`MultiRegistry::all`] reports the highest version available on all registries.
+/// * [`MultiRegistry::installed`] reports the latest version of the registry the application was
+///   installed from, which is detected from the path of the executable (e.g. `~/.cargo/bin`,
+///   `node_modules`, `site-packages` or a Homebrew `Cellar`). If it cannot be detected, all
+///   registries are checked.
+///
+/// # Examples
+///
+/// ```rust
+/// use update_informer::{registry::{Crates, CratesIndex, MultiRegistry}, Check};
+///
+/// let registry = MultiRegistry::installed((Crates, CratesIndex));
+///
+/// let informer = update_informer::new(registry, "crate_name", "0.1.0");
+/// let _ = informer.check_version();
+/// ```
+pub struct MultiRegistry<L: RegistryList> {
+    registries: L,
+    mode: Mode,
+}
+
+impl<L: RegistryList> MultiRegistry<L> {
+    /// Constructs a new `MultiRegistry` reporting versions available on all registries.
+    ///
+    /// # Arguments
+    ///
+    /// * `registries` - A tuple or a list of registries.
+    pub fn all(registries: L) -> Self {
+        Self {
+            registries,
+            mode: Mode::All,
+        }
+    }
+
+    /// Constructs a new `MultiRegistry` reporting versions available on the registry
+    /// the application was installed from.
+    ///
+    /// # Arguments
+    ///
+    /// * `registries` - A tuple or a list of registries.
+    pub fn installed(registries: L) -> Self {
+        Self {
+            registries,
+            mode: Mode::Installed(None),
+        }
+    }
+
+    /// Sets the name of the registry the application was installed from, e.g. `npm`,
+    /// instead of detecting it.
+    pub fn installed_from<N: Into<String>>(self, name: N) -> Self {
+        Self {
+            mode: Mode::Installed(Some(name.into())),
+            ..self
+        }
+    }
+
+    /// Returns the position of the registry the application was installed from.
+    fn installed_index(&self) -> Option<usize> {
+        let name = match &self.mode {
+            Mode::All => return None,
+            Mode::Installed(Some(name)) => name.clone(),
+            Mode::Installed(None) => {
+                let exe = env::current_exe().ok()?;
+                install_source(&exe)?.to_string()
+            }
+        };
+
+        self.registries
+            .sources()
+            .iter()
+            .position(|source| source.split('#').next() == Some(name.as_str()))
+    }
+}
+
+impl<L: RegistryList> Registry for MultiRegistry<L> {
+    const NAME: &'static str = "multi";
+
+    fn cache_name(&self) -> String {
+        // The registry the application was installed from changes the version found
+        let mode = match self.installed_index() {
+            Some(index) => self.registries.sources()[index].clone(),
+            None => "all".to_string(),
+        };
+
+        cache_name::with_registries(Self::NAME, &self.registries.cache_names(), &mode)
+    }
+
+    fn get_latest_version<T: HttpClient>(
+        &self,
+        http_client: GenericHttpClient<T>,
+        pkg: &Package,
+    ) -> Result<Option<String>> {
+        let release = self.get_latest_release(http_client, pkg)?;
+
+        Ok(release.map(|r| r.version))
+    }
+
+    fn get_latest_release<T: HttpClient>(
+        &self,
+        http_client: GenericHttpClient<T>,
+        pkg: &Package,
+    ) -> Result<Option<Release>> {
+        let sources = self.registries.sources();
+
+        if let Some(index) = self.installed_index() {
+            let release = self
+                .registries
+                .get_latest_release_at(index, http_client, pkg)?;
+
+            return Ok(release.map(|r| with_source(r, sources[index].clone())));
+        }
+
+        let scheme = self.version_scheme();
+        let mut oldest: Option<(Version, VersionScheme, Release)> = None;
+
+        for (index, source) in sources.into_iter().enumerate() {
+            let release = self
+                .registries
+                .get_latest_release_at(index, http_client.clone(), pkg)?;
+
+            // The version is not available on all registries
+            let Some(release) = release else {
+                return Ok(None);
+            };
+
+            let release_scheme = self.registries.version_scheme_at(index);
+            let version = Version::parse_with_scheme(&release.version, release_scheme)?;
+            if oldest.as_ref().is_none_or(|(v, _, _)| &version < v) {
+                oldest = Some((version, release_scheme, with_source(release, source)));
+            }
+        }
+
+        Ok(oldest.map(|(version, release_scheme, release)| {
+            // The version is parsed with the scheme of the first registry later on
+            if release_scheme == VersionScheme::Pep440 && scheme == VersionScheme::Semver {
+                Release {
+                    version: version.semver().to_string(),
+                    ..release
+                }
+            } else {
+                release
+            }
+        }))
+    }
+
+    /// Returns the versioning scheme of the registry the application was installed from,
+    /// or of the first registry.
+    fn version_scheme(&self) -> VersionScheme {
+        match self.installed_index() {
+            Some(index) => self.registries.version_scheme_at(index),
+            None => self.registries.version_scheme(),
+        }
+    }
+}
+
+/// Returns the name of the registry an executable was installed from, based on its path.
+fn install_source(exe: &Path) -> Option<&'static str> {
+    let components: Vec<_> = exe
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_lowercase())
+        .collect();
+    let contains = |name: &str| components.iter().any(|c| c == name);
+    let contains_pair = |first: &str, second: &str| {
+        components
+            .windows(2)
+            .any(|pair| pair[0] == first && pair[1] == second)
+    };
+
+    if contains("node_modules") {
+        return Some("npm");
+    }
+
+    if contains("site-packages") || contains("dist-packages") || contains("pipx") {
+        return Some("pypi");
+    }
+
+    // A virtual environment, e.g. `.venv/bin/tool`
+    let venv = exe.parent().and_then(Path::parent);
+    if venv.is_some_and(|dir| dir.join("pyvenv.cfg").is_file()) {
+        return Some("pypi");
+    }
+
+    if contains("cellar") || contains("linuxbrew") {
+        return Some("homebrew");
+    }
+
+    if contains_pair(".cargo", "bin") {
+        return Some("crates");
+    }
+
+    if contains_pair("go", "bin") {
+        return Some("goproxy");
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{http_client, test_helper::mock_http};
+    use serde::Deserialize;
+    use std::time::Duration;
+
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    #[derive(Deserialize)]
+    struct Response {
+        version: Option<String>,
+    }
+
+    /// A registry reading `{"version": "..."}` from a path of the mock server.
+    struct Endpoint(&'static str);
+
+    impl Registry for Endpoint {
+        const NAME: &'static str = "endpoint";
+
+        fn get_latest_version<T: HttpClient>(
+            &self,
+            http_client: GenericHttpClient<T>,
+            _pkg: &Package,
+        ) -> Result<Option<String>> {
+            let url = format!("{}{}", mockito::server_url(), self.0);
+            let resp = http_client.get::<Response>(&url)?;
+
+            Ok(resp.version)
+        }
+    }
+
+    /// The same as `Endpoint`, but with another name.
+    struct Other(&'static str);
+
+    impl Registry for Other {
+        const NAME: &'static str = "other";
+
+        fn get_latest_version<T: HttpClient>(
+            &self,
+            http_client: GenericHttpClient<T>,
+            pkg: &Package,
+        ) -> Result<Option<String>> {
+            Endpoint(self.0).get_latest_version(http_client, pkg)
+        }
+    }
+
+    /// The same as `Endpoint`, but with PEP 440 versions.
+    struct Python(&'static str);
+
+    impl Registry for Python {
+        const NAME: &'static str = "pypi";
+
+        fn get_latest_version<T: HttpClient>(
+            &self,
+            http_client: GenericHttpClient<T>,
+            pkg: &Package,
+        ) -> Result<Option<String>> {
+            Endpoint(self.0).get_latest_version(http_client, pkg)
+        }
+
+        fn version_scheme(&self) -> VersionScheme {
+            VersionScheme::Pep440
+        }
+    }
+
+    fn get_latest_release<L: RegistryList>(registry: MultiRegistry<L>) -> Result<Option<Release>> {
+        let pkg = Package::new("repo", "0.1.0").unwrap();
+        let client = http_client::new(http_client::DefaultHttpClient::default(), TIMEOUT);
+
+        registry.get_latest_release(client, &pkg)
+    }
+
+    #[test]
+    fn all_test() {
+        let _first = mock_http("/multi/first", 200, r#"{"version":"1.2.0"}"#);
+        let _second = mock_http("/multi/second", 200, r#"{"version":"1.1.0"}"#);
+
+        let registry = MultiRegistry::all((Endpoint("/multi/first"), Other("/multi/second")));
+        let release = get_latest_release(registry).expect("get result");
+
+        let expected = Release {
+            source: Some("other".to_string()),
+            ..Release::new("1.1.0")
+        };
+        assert_eq!(release, Some(expected));
+    }
+
+    #[test]
+    fn all_not_published_test() {
+        let _first = mock_http("/multi/published", 200, r#"{"version":"1.2.0"}"#);
+        let _second = mock_http("/multi/unpublished", 200, r#"{"version":null}"#);
+
+        let registry =
+            MultiRegistry::all((Endpoint("/multi/published"), Other("/multi/unpublished")));
+
+        assert_eq!(get_latest_release(registry).expect("get result"), None);
+    }
+
+    #[test]
+    fn installed_from_test() {
+        let _first = mock_http("/multi/installed", 200, r#"{"version":"1.2.0"}"#);
+        let second = mock_http("/multi/unused", 200, r#"{"version":"1.1.0"}"#).expect(0);
+
+        let registry =
+            MultiRegistry::installed((Other("/multi/unused"), Endpoint("/multi/installed")))
+                .installed_from("endpoint");
+        let release = get_latest_release(registry).expect("get result");
+
+        let expected = Release {
+            source: Some("endpoint".to_string()),
+            ..Release::new("1.2.0")
+        };
+        assert_eq!(release, Some(expected));
+        second.assert();
+    }
+
+    #[test]
+    fn all_version_schemes_test() {
+        let _first = mock_http("/multi/semver", 200, r#"{"version":"1.2.0"}"#);
+        let _second = mock_http("/multi/pep440", 200, r#"{"version":"1.2.0rc1"}"#);
+
+        let registry = MultiRegistry::all((Endpoint("/multi/semver"), Python("/multi/pep440")));
+        assert_eq!(registry.version_scheme(), VersionScheme::Semver);
+
+        let release = get_latest_release(registry).expect("get result");
+        let expected = Release {
+            source: Some("pypi".to_string()),
+            ..Release::new("1.2.0-rc.1")
+        };
+        assert_eq!(release, Some(expected));
+    }
+
+    #[test]
+    fn installed_version_scheme_test() {
+        let _first = mock_http("/multi/installed-pep440", 200, r#"{"version":"1.2.0rc1"}"#);
+
+        let registry = MultiRegistry::installed((
+            Endpoint("/multi/unused"),
+            Python("/multi/installed-pep440"),
+        ))
+        .installed_from("pypi");
+        assert_eq!(registry.version_scheme(), VersionScheme::Pep440);
+
+        let release = get_latest_release(registry).expect("get result");
+        let expected = Release {
+            source: Some("pypi".to_string()),
+            ..Release::new("1.2.0rc1")
+        };
+        assert_eq!(release, Some(expected));
+    }
+
+    #[test]
+    fn cache_name_test() {
+        let registry = MultiRegistry::all((Endpoint("/first"), Other("/second")));
+        let cache_name = registry.cache_name();
+        assert!(cache_name.starts_with("multi-"), "{cache_name}");
+
+        let swapped = MultiRegistry::all((Other("/second"), Endpoint("/first")));
+        assert_ne!(swapped.cache_name(), cache_name);
+
+        let installed = MultiRegistry::installed((Endpoint("/first"), Other("/second")))
+            .installed_from("other");
+        assert_ne!(installed.cache_name(), cache_name);
+    }
+
+    #[test]
+    fn install_source_test() {
+        let cases = [
+            ("/home/user/.cargo/bin/tool", Some("crates")),
+            (
+                "/usr/lib/node_modules/@scope/tool-linux-x64/bin/tool",
+                Some("npm"),
+            ),
+            ("/usr/lib/python3/dist-packages/tool/bin/tool", Some("pypi")),
+            ("/home/user/.local/pipx/venvs/tool/bin/tool", Some("pypi")),
+            ("/opt/homebrew/Cellar/tool/1.0.0/bin/tool", Some("homebrew")),
+            ("/home/user/go/bin/tool", Some("goproxy")),
+            ("/usr/local/bin/tool", None),
+        ];
+
+        for (path, expected) in cases {
+            assert_eq!(install_source(Path::new(path)), expected, "{path}");
+        }
+    }
+}
@@ -1,8 +1,15 @@
 use crate::{
+    base64,
     http_client::{GenericHttpClient, HttpClient},
+    registry::cache_name,
     Package, Registry, Result,
 };
 use serde::Deserialize;
+use std::{
+    env, fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
 
 #[cfg(not(test))]
 const REGISTRY_URL: &str = "https://registry.npmjs.org";
@@ -29,20 +36,358 @@ impl Registry for Npm {
     const NAME: &'static str = "npm";
 
     fn get_latest_version<T: HttpClient>(
+        &self,
+        http_client: GenericHttpClient<T>,
+        pkg: &Package,
+    ) -> Result<Option<String>> {
+        NpmRegistry::default().get_latest_version(http_client, pkg)
+    }
+}
+
+/// Credentials used to access an NPM registry.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Auth {
+    /// A token sent as `Authorization: Bearer <token>`.
+    Token(String),
+    /// Credentials sent as `Authorization: Basic <base64(username:password)>`.
+    Basic { username: String, password: String },
+    /// Already encoded `username:password` pair (the `_auth` setting).
+    Encoded(String),
+}
+
+impl Auth {
+    fn header_value(&self) -> String {
+        match self {
+            Auth::Token(token) => format!("Bearer {token}"),
+            Auth::Basic { username, password } => {
+                format!("Basic {}", base64::encode(format!("{username}:{password}")))
+            }
+            Auth::Encoded(auth) => format!("Basic {auth}"),
+        }
+    }
+}
+
+/// A configurable NPM registry, e.g. a private Verdaccio or Artifactory instance.
+///
+/// Supports a custom registry URL, scope-to-registry mapping and bearer or basic
+/// authentication. The settings can also be read from `.npmrc` files, see
+/// [`NpmRegistry::from_npmrc`].
+///
+/// # Examples
+///
+/// ```rust
+/// use update_informer::{registry::NpmRegistry, Check};
+///
+/// let registry = NpmRegistry::new("https://npm.example.com")
+///     .scope("@acme", "https://npm.acme.com")
+///     .auth_token("https://npm.acme.com", "secret");
+///
+/// let informer = update_informer::new(registry, "@acme/cli", "0.1.0");
+/// let _ = informer.check_version();
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NpmRegistry {
+    registry: Option<String>,
+    scopes: Vec<(String, String)>,
+    auth: Vec<(String, Auth)>,
+}
+
+impl NpmRegistry {
+    /// Constructs a new `NpmRegistry` with the given default registry URL.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - A registry URL, e.g. `https://npm.example.com`.
+    pub fn new<U: Into<String>>(url: U) -> Self {
+        Self::default().registry(url)
+    }
+
+    /// Constructs a new `NpmRegistry` using the same configuration `npm install` would see.
+    ///
+    /// Reads the user config (`$NPM_CONFIG_USERCONFIG` or `~/.npmrc`) and then the project
+    /// `.npmrc` in the current directory, which takes precedence. The `NPM_CONFIG_REGISTRY`
+    /// environment variable overrides the default registry. `${VAR}` references in values
+    /// are expanded from the environment.
+    ///
+    /// Supported settings are `registry=`, `@scope:registry=`, `//host/path/:_authToken=`,
+    /// `//host/path/:username=` with `//host/path/:_password=`, and `//host/path/:_auth=`.
+    pub fn from_npmrc() -> Result<Self> {
+        let mut registry = Self::default();
+
+        for path in npmrc_paths() {
+            match fs::read_to_string(&path) {
+                Ok(contents) => registry.apply_npmrc(&contents),
+                Err(e) if e.kind() == ErrorKind::NotFound => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        if let Some(url) = env_var("NPM_CONFIG_REGISTRY") {
+            registry.registry = Some(url);
+        }
+
+        Ok(registry)
+    }
+
+    /// Sets the default registry URL.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - A registry URL, e.g. `https://npm.example.com`.
+    pub fn registry<U: Into<String>>(self, url: U) -> Self {
+        Self {
+            registry: Some(url.into()),
+            ..self
+        }
+    }
+
+    /// Maps a package scope to a registry URL.
+    ///
+    /// # Arguments
+    ///
+    /// * `scope` - A package scope, e.g. `@acme`.
+    /// * `url` - A registry URL used for packages of this scope.
+    pub fn scope<S: Into<String>, U: Into<String>>(mut self, scope: S, url: U) -> Self {
+        self.set_scope(scope.into(), url.into());
+        self
+    }
+
+    /// Sets a bearer token for requests to the registry.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - A registry URL the token belongs to.
+    /// * `token` - An authentication token.
+    pub fn auth_token<U: AsRef<str>, T: Into<String>>(mut self, url: U, token: T) -> Self {
+        self.set_auth(nerf_dart(url.as_ref()), Auth::Token(token.into()));
+        self
+    }
+
+    /// Sets basic authentication credentials for requests to the registry.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - A registry URL the credentials belong to.
+    /// * `username` - A user name.
+    /// * `password` - A password (not base64-encoded).
+    pub fn basic_auth<U, N, P>(mut self, url: U, username: N, password: P) -> Self
+    where
+        U: AsRef<str>,
+        N: Into<String>,
+        P: Into<String>,
+    {
+        let auth = Auth::Basic {
+            username: username.into(),
+            password: password.into(),
+        };
+
+        self.set_auth(nerf_dart(url.as_ref()), auth);
+        self
+    }
+
+    fn set_scope(&mut self, mut scope: String, url: String) {
+        if !scope.starts_with('@') {
+            scope.insert(0, '@');
+        }
+
+        self.scopes.retain(|(s, _)| s != &scope);
+        self.scopes.push((scope, url));
+    }
+
+    fn set_auth(&mut self, key: String, auth: Auth) {
+        self.auth.retain(|(k, _)| k != &key);
+        self.auth.push((key, auth));
+    }
+
+    fn apply_npmrc(&mut self, contents: &str) {
+        let mut usernames = Vec::new();
+        let mut passwords = Vec::new();
+
+        for (key, value) in parse_npmrc(contents) {
+            if key == "registry" {
+                self.registry = Some(value);
+            } else if let Some(scope) = key.strip_suffix(":registry") {
+                self.set_scope(scope.to_string(), value);
+            } else if let Some(prefix) = key.strip_suffix(":_authToken") {
+                self.set_auth(prefix.to_string(), Auth::Token(value));
+            } else if let Some(prefix) = key.strip_suffix(":_auth") {
+                self.set_auth(prefix.to_string(), Auth::Encoded(value));
+            } else if let Some(prefix) = key.strip_suffix(":username") {
+                usernames.push((prefix.to_string(), value));
+            } else if let Some(prefix) = key.strip_suffix(":_password") {
+                passwords.push((prefix.to_string(), value));
+            }
+        }
+
+        for (prefix, username) in usernames {
+            let password = passwords
+                .iter()
+                .find(|(p, _)| p == &prefix)
+                .and_then(|(_, password)| base64::decode(password))
+                .and_then(|password| String::from_utf8(password).ok());
+
+            if let Some(password) = password {
+                self.set_auth(prefix, Auth::Basic { username, password });
+            }
+        }
+    }
+
+    /// Returns the registry URL used for the package.
+    fn registry_url(&self, pkg: &Package) -> String {
+        let scope = pkg.to_string();
+        let scope = scope.split_once('/').map(|(scope, _)| scope);
+
+        scope
+            .and_then(|scope| self.scopes.iter().find(|(s, _)| s == scope))
+            .map(|(_, url)| url.clone())
+            .or_else(|| self.registry.clone())
+            .unwrap_or_else(get_base_url)
+    }
+
+    /// Returns credentials with the longest matching prefix for the registry URL.
+    fn auth_for(&self, url: &str) -> Option<&Auth> {
+        let url = nerf_dart(url);
+
+        self.auth
+            .iter()
+            .filter(|(prefix, _)| url.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, auth)| auth)
+    }
+}
+
+impl Registry for NpmRegistry {
+    const NAME: &'static str = "npm";
+
+    /// Returns `npm` for the public registry, or e.g. `npm-npm.example.com-1a2b3c4d`
+    /// identifying the default and the scoped registry URLs.
+    fn cache_name(&self) -> String {
+        if self.registry.is_none() && self.scopes.is_empty() {
+            return Self::NAME.to_string();
+        }
+
+        let url = self.registry.clone().unwrap_or_else(get_base_url);
+        let scopes: Vec<String> = self
+            .scopes
+            .iter()
+            .map(|(scope, url)| format!("{scope}={url}"))
+            .collect();
+
+        cache_name::with_url(Self::NAME, &url, &scopes.join("\n"))
+    }
+
+    fn get_latest_version<T: HttpClient>(
+        &self,
         http_client: GenericHttpClient<T>,
         pkg: &Package,
     ) -> Result<Option<String>> {
-        let url = format!("{}/{}/latest", get_base_url(), pkg);
+        let base_url = self.registry_url(pkg);
+        let url = format!("{}/{}/latest", base_url.trim_end_matches('/'), pkg);
+
+        let authorization = self.auth_for(&base_url).map(Auth::header_value);
+        let http_client = match &authorization {
+            Some(value) => http_client.add_header("Authorization", value),
+            None => http_client,
+        };
+
         let resp = http_client.get::<Response>(&url)?;
 
         Ok(Some(resp.version))
     }
 }
 
+/// Converts a registry URL to the form used for credential keys in `.npmrc`,
+/// e.g. `https://npm.example.com/repo` becomes `//npm.example.com/repo/`.
+fn nerf_dart(url: &str) -> String {
+    let url = url.split_once(':').map_or(url, |(scheme, rest)| {
+        if scheme.starts_with("http") {
+            rest
+        } else {
+            url
+        }
+    });
+
+    let mut url = url.to_string();
+    if !url.starts_with("//") {
+        url.insert_str(0, "//");
+    }
+    if !url.ends_with('/') {
+        url.push('/');
+    }
+
+    url
+}
+
+fn npmrc_paths() -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+
+    if let Some(path) = env_var("NPM_CONFIG_USERCONFIG") {
+        paths.push(PathBuf::from(path));
+    } else if let Some(home) = env::var_os("HOME").or_else(|| env::var_os("USERPROFILE")) {
+        paths.push(Path::new(&home).join(".npmrc"));
+    }
+
+    if let Ok(dir) = env::current_dir() {
+        paths.push(dir.join(".npmrc"));
+    }
+
+    paths
+}
+
+/// Reads an npm config variable, which npm accepts in both upper and lower case.
+fn env_var(name: &str) -> Option<String> {
+    env::var(name)
+        .or_else(|_| env::var(name.to_lowercase()))
+        .ok()
+        .filter(|v| !v.is_empty())
+}
+
+/// Parses `key=value` pairs of an `.npmrc` file, expanding `${VAR}` references.
+fn parse_npmrc(contents: &str) -> Vec<(String, String)> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with(';'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| {
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .unwrap_or(value);
+
+            (expand_env(key.trim()), expand_env(value))
+        })
+        .collect()
+}
+
+fn expand_env(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut rest = value;
+
+    while let Some(start) = rest.find("${") {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+
+        result.push_str(&rest[..start]);
+        let name = &rest[start + 2..start + end];
+        result.push_str(&env::var(name).unwrap_or_default());
+        rest = &rest[start + end + 1..];
+    }
+
+    result.push_str(rest);
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{http_client, test_helper::mock_npm};
+    use crate::{
+        http_client,
+        test_helper::{mock_npm, write_globals, EnvVar},
+    };
+    use mockito::mock;
     use std::time::Duration;
 
     const PKG_NAME: &str = "turbo";
@@ -53,11 +398,11 @@ mod tests {
     fn failure_test() {
         let raw_version = "0.1.0";
         let pkg = Package::new(PKG_NAME, raw_version).unwrap();
-        let client = http_client::new(http_client::DefaultHttpClient {}, TIMEOUT);
+        let client = http_client::new(http_client::DefaultHttpClient::default(), TIMEOUT);
         let data_path = format!("{}/not_found.html", FIXTURES_PATH);
         let _mock = mock_npm(&pkg, 404, &data_path);
 
-        let result = Npm::get_latest_version(client, &pkg);
+        let result = Npm.get_latest_version(client, &pkg);
         assert!(result.is_err());
     }
 
@@ -65,14 +410,88 @@ mod tests {
     fn success_test() {
         let raw_version = "1.6.2";
         let pkg = Package::new(PKG_NAME, raw_version).unwrap();
-        let client = http_client::new(http_client::DefaultHttpClient {}, TIMEOUT);
+        let client = http_client::new(http_client::DefaultHttpClient::default(), TIMEOUT);
         let data_path = format!("{}/latest.json", FIXTURES_PATH);
         let (_mock, _data) = mock_npm(&pkg, 200, &data_path);
 
         let latest_version = "1.6.3".to_string();
-        let result = Npm::get_latest_version(client, &pkg);
+        let result = Npm.get_latest_version(client, &pkg);
 
         assert!(result.is_ok());
         assert_eq!(result.expect("get result"), Some(latest_version));
     }
+
+    #[test]
+    fn scoped_registry_with_token_test() {
+        let pkg = Package::new("@acme/private-cli", "0.1.0").unwrap();
+        let client = http_client::new(http_client::DefaultHttpClient::default(), TIMEOUT);
+        let data = fs::read_to_string(format!("{}/latest.json", FIXTURES_PATH)).unwrap();
+        let scope_url = format!("{}/acme", mockito::server_url());
+        let _mock = mock("GET", "/acme/@acme/private-cli/latest")
+            .match_header("Authorization", "Bearer secret")
+            .with_status(200)
+            .with_header("Content-Type", "application/json")
+            .with_body(data)
+            .create();
+
+        let registry = NpmRegistry::new("https://registry.invalid")
+            .scope("acme", &scope_url)
+            .auth_token(&scope_url, "secret");
+        let result = registry.get_latest_version(client, &pkg);
+
+        assert_eq!(result.expect("get result"), Some("1.6.3".to_string()));
+    }
+
+    #[test]
+    fn npmrc_test() {
+        let _globals = write_globals();
+        let _env = EnvVar::set("UPDATE_INFORMER_NPM_TOKEN", "from-env");
+
+        let mut registry = NpmRegistry::default();
+        registry.apply_npmrc(
+            r#"
+            # comment
+            registry=https://npm.example.com/
+            @acme:registry = "https://npm.acme.com/repo/"
+            //npm.acme.com/:_authToken=${UPDATE_INFORMER_NPM_TOKEN}
+            //npm.example.com/:username=user
+            //npm.example.com/:_password=cGFzc3dvcmQ=
+            "#,
+        );
+
+        let pkg = Package::new("@acme/cli", "0.1.0").unwrap();
+        assert_eq!(registry.registry_url(&pkg), "https://npm.acme.com/repo/");
+        assert_eq!(
+            registry.auth_for("https://npm.acme.com/repo/"),
+            Some(&Auth::Token("from-env".to_string()))
+        );
+
+        let pkg = Package::new("cli", "0.1.0").unwrap();
+        assert_eq!(registry.registry_url(&pkg), "https://npm.example.com/");
+
+        let auth = registry.auth_for("https://npm.example.com/").unwrap();
+        assert_eq!(auth.header_value(), "Basic dXNlcjpwYXNzd29yZA==");
+    }
+
+    #[test]
+    fn cache_name_test() {
+        assert_eq!(NpmRegistry::default().cache_name(), Npm.cache_name());
+
+        let registry = NpmRegistry::new("https://npm.example.com");
+        let cache_name = registry.cache_name();
+        assert!(
+            cache_name.starts_with("npm-npm.example.com-"),
+            "{cache_name}"
+        );
+
+        let scoped = registry.scope("acme", "https://npm.acme.com");
+        assert_ne!(scoped.cache_name(), cache_name);
+    }
+
+    #[test]
+    fn nerf_dart_test() {
+        assert_eq!(nerf_dart("https://npm.example.com"), "//npm.example.com/");
+        assert_eq!(nerf_dart("http://host:8080/repo/"), "//host:8080/repo/");
+        assert_eq!(nerf_dart("//host/"), "//host/");
+    }
 }
@@ -0,0 +1,90 @@
+use crate::{
+    http_client::{GenericHttpClient, HttpClient},
+    Package, Registry, Result, Version,
+};
+use serde::Deserialize;
+
+#[cfg(not(test))]
+const REGISTRY_URL: &str = "https://api.nuget.org";
+
+#[derive(Deserialize)]
+struct Response {
+    versions: Vec<String>,
+}
+
+/// The NuGet package registry, using the flat container (package base address) resource.
+///
+/// Pre-releases are only considered if the current version is a pre-release.
+pub struct NuGet;
+
+#[cfg(not(test))]
+fn get_base_url() -> String {
+    format!("{REGISTRY_URL}/v3-flatcontainer")
+}
+
+#[cfg(test)]
+fn get_base_url() -> String {
+    format!("{}/v3-flatcontainer", &mockito::server_url())
+}
+
+impl Registry for NuGet {
+    const NAME: &'static str = "nuget";
+
+    fn get_latest_version<T: HttpClient>(
+        &self,
+        http_client: GenericHttpClient<T>,
+        pkg: &Package,
+    ) -> Result<Option<String>> {
+        // Package IDs must be lowercase in the flat container
+        let id = pkg.to_string().to_lowercase();
+        let url = format!("{}/{}/index.json", get_base_url(), id);
+        let resp = http_client.get::<Response>(&url)?;
+
+        let prereleases = !pkg.version().semver().pre.is_empty();
+
+        let latest = resp
+            .versions
+            .into_iter()
+            .filter_map(|raw| Version::parse(&raw).ok().map(|v| (v, raw)))
+            .filter(|(v, _)| prereleases || v.semver().pre.is_empty())
+            .max_by(|(a, _), (b, _)| a.cmp(b))
+            .map(|(_, raw)| raw);
+
+        Ok(latest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{http_client, test_helper::mock_nuget};
+    use std::time::Duration;
+
+    const PKG_NAME: &str = "Newtonsoft.Json";
+    const FIXTURES_PATH: &str = "tests/fixtures/registry/nuget";
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    #[test]
+    fn failure_test() {
+        let pkg = Package::new("missing", "0.1.0").unwrap();
+        let client = http_client::new(http_client::DefaultHttpClient::default(), TIMEOUT);
+        let data_path = format!("{}/not_found.xml", FIXTURES_PATH);
+        let _mock = mock_nuget(&pkg, 404, &data_path);
+
+        let result = NuGet.get_latest_version(client, &pkg);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn success_test() {
+        let pkg = Package::new(PKG_NAME, "13.0.1").unwrap();
+        let client = http_client::new(http_client::DefaultHttpClient::default(), TIMEOUT);
+        let data_path = format!("{}/index.json", FIXTURES_PATH);
+        let _mock = mock_nuget(&pkg, 200, &data_path);
+
+        let result = NuGet.get_latest_version(client, &pkg);
+
+        assert!(result.is_ok());
+        assert_eq!(result.expect("get result"), Some("13.0.3".to_string()));
+    }
+}
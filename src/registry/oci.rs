@@ -0,0 +1,399 @@
+use crate::{
+    http_client::{GenericHttpClient, HttpClient, HttpError},
+    registry::cache_name,
+    Package, Registry, Result, Version,
+};
+use serde::Deserialize;
+
+#[cfg(not(test))]
+const DOCKER_HUB_URL: &str = "https://registry-1.docker.io";
+#[cfg(not(test))]
+const DOCKER_HUB_AUTH_URL: &str = "https://auth.docker.io/token";
+#[cfg(not(test))]
+const GHCR_URL: &str = "https://ghcr.io";
+
+#[derive(Deserialize)]
+struct TagsResponse {
+    #[serde(default)]
+    tags: Option<Vec<String>>,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    token: Option<String>,
+    access_token: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct TokenAuth {
+    realm: String,
+    service: String,
+}
+
+/// A container image registry implementing the OCI distribution API, e.g. Docker Hub or GHCR.
+///
+/// Image tags that are valid semantic versions (with an optional `v` prefix) are considered,
+/// as well as two-part tags such as `1.2`, which are read as `1.2.0`, while tags like `latest`
+/// or `stable` are ignored. Tags with a suffix such as `1.2.3-alpine`
+/// are pre-releases and only considered if the current version is a pre-release.
+///
+/// # Examples
+///
+/// ```rust
+/// use update_informer::{registry::Oci, Check};
+///
+/// let informer = update_informer::new(Oci::ghcr(), "owner/image", "0.1.0");
+/// let _ = informer.check_version();
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Oci {
+    url: String,
+    auth: Option<TokenAuth>,
+    default_namespace: Option<String>,
+}
+
+impl Oci {
+    /// Constructs a new `Oci` registry that is accessed anonymously.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - A registry URL, e.g. `https://registry.example.com`.
+    pub fn new<U: Into<String>>(url: U) -> Self {
+        Self {
+            url: url.into().trim_end_matches('/').to_string(),
+            auth: None,
+            default_namespace: None,
+        }
+    }
+
+    /// Constructs a new `Oci` registry for Docker Hub.
+    ///
+    /// Official images without an owner, e.g. `nginx`, are looked up in the `library` namespace.
+    pub fn docker_hub() -> Self {
+        let (url, auth_url) = docker_hub_urls();
+
+        Self {
+            default_namespace: Some("library".to_string()),
+            ..Self::new(url).token_auth(auth_url, "registry.docker.io")
+        }
+    }
+
+    /// Constructs a new `Oci` registry for the GitHub Container Registry (`ghcr.io`).
+    pub fn ghcr() -> Self {
+        let url = ghcr_url();
+        let auth_url = format!("{url}/token");
+
+        Self::new(url).token_auth(auth_url, "ghcr.io")
+    }
+
+    /// Sets the anonymous token flow used to get a pull token before listing tags.
+    ///
+    /// # Arguments
+    ///
+    /// * `realm` - A token endpoint URL, e.g. `https://auth.docker.io/token`.
+    /// * `service` - A service name, e.g. `registry.docker.io`.
+    pub fn token_auth<R: Into<String>, S: Into<String>>(self, realm: R, service: S) -> Self {
+        let auth = TokenAuth {
+            realm: realm.into(),
+            service: service.into(),
+        };
+
+        Self {
+            auth: Some(auth),
+            ..self
+        }
+    }
+
+    fn repository(&self, pkg: &Package) -> String {
+        let name = pkg.to_string();
+
+        match &self.default_namespace {
+            Some(namespace) if !name.contains('/') => format!("{namespace}/{name}"),
+            _ => name,
+        }
+    }
+
+    /// Resolves a link relative to the registry, e.g. `/v2/owner/image/tags/list?last=1.0.0`.
+    fn resolve(&self, link: &str) -> String {
+        if link.contains("://") {
+            return link.to_string();
+        }
+
+        // The link is relative to the origin of the registry
+        let scheme_end = self.url.find("://").map_or(0, |pos| pos + 3);
+        let origin_end = self.url[scheme_end..]
+            .find('/')
+            .map_or(self.url.len(), |pos| scheme_end + pos);
+
+        format!(
+            "{}/{}",
+            &self.url[..origin_end],
+            link.trim_start_matches('/')
+        )
+    }
+}
+
+#[cfg(not(test))]
+fn docker_hub_urls() -> (String, String) {
+    (DOCKER_HUB_URL.to_string(), DOCKER_HUB_AUTH_URL.to_string())
+}
+
+#[cfg(test)]
+fn docker_hub_urls() -> (String, String) {
+    let url = mockito::server_url();
+    (url.clone(), format!("{url}/token"))
+}
+
+#[cfg(not(test))]
+fn ghcr_url() -> String {
+    GHCR_URL.to_string()
+}
+
+#[cfg(test)]
+fn ghcr_url() -> String {
+    mockito::server_url()
+}
+
+impl Registry for Oci {
+    const NAME: &'static str = "oci";
+
+    /// Returns e.g. `oci-ghcr.io-1a2b3c4d`, identifying the registry URL.
+    fn cache_name(&self) -> String {
+        let namespace = self.default_namespace.as_deref().unwrap_or_default();
+
+        cache_name::with_url(Self::NAME, &self.url, namespace)
+    }
+
+    fn get_latest_version<T: HttpClient>(
+        &self,
+        http_client: GenericHttpClient<T>,
+        pkg: &Package,
+    ) -> Result<Option<String>> {
+        let repository = self.repository(pkg);
+
+        let token = match &self.auth {
+            Some(auth) => {
+                let url = format!(
+                    "{}?service={}&scope=repository:{repository}:pull",
+                    auth.realm, auth.service
+                );
+                let resp = http_client.get::<TokenResponse>(&url)?;
+
+                let token = resp.token.or(resp.access_token);
+                Some(token.ok_or("token endpoint did not return a token")?)
+            }
+            None => None,
+        };
+
+        let authorization = token.map(|token| format!("Bearer {token}"));
+        let http_client = match &authorization {
+            Some(value) => http_client.add_header("Authorization", value),
+            None => http_client,
+        };
+
+        // Registries return the tags in pages, linking to the next one in the `Link` header
+        let mut tags = Vec::new();
+        let mut next = Some(format!("{}/v2/{repository}/tags/list", self.url));
+        while let Some(url) = next {
+            let resp = http_client.get_response(&url)?;
+            if !resp.is_success() {
+                return Err(HttpError::Status(resp.status).into());
+            }
+
+            next = resp
+                .header("Link")
+                .and_then(next_link)
+                .map(|link| self.resolve(link))
+                .filter(|link| *link != url);
+            tags.extend(resp.json::<TagsResponse>()?.tags.unwrap_or_default());
+        }
+
+        let prereleases = !pkg.version().semver().pre.is_empty();
+
+        Ok(latest_tag(tags, prereleases))
+    }
+}
+
+/// Returns the URL of the next page from a header like `</v2/...?last=1.0.0&n=100>; rel="next"`.
+fn next_link(header: &str) -> Option<&str> {
+    header.split(',').find_map(|link| {
+        let (url, params) = link.split_once(';')?;
+        let is_next = params
+            .split(';')
+            .any(|param| matches!(param.trim(), "rel=\"next\"" | "rel=next"));
+
+        is_next.then(|| url.trim().trim_start_matches('<').trim_end_matches('>'))
+    })
+}
+
+/// Returns the highest tag that is a semantic version, padding two-part tags to three parts.
+fn latest_tag(tags: Vec<String>, prereleases: bool) -> Option<String> {
+    tags.into_iter()
+        .map(|tag| pad(&tag))
+        .filter_map(|tag| Version::parse(&tag).ok().map(|version| (version, tag)))
+        .filter(|(version, _)| prereleases || version.semver().pre.is_empty())
+        .max_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, tag)| tag)
+}
+
+/// Pads tags such as `1.2` or `v1.2-alpine` to `1.2.0` or `v1.2.0-alpine`.
+fn pad(tag: &str) -> String {
+    let (core, suffix) = match tag.find(['-', '+']) {
+        Some(pos) => tag.split_at(pos),
+        None => (tag, ""),
+    };
+    let numbers = core.strip_prefix('v').unwrap_or(core);
+    let parts: Vec<&str> = numbers.split('.').collect();
+
+    if parts.len() == 2
+        && parts
+            .iter()
+            .all(|part| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()))
+    {
+        return format!("{core}.0{suffix}");
+    }
+
+    tag.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{http_client, test_helper::mock_oci_tags};
+    use mockito::{mock, Matcher};
+    use std::time::Duration;
+
+    const FIXTURES_PATH: &str = "tests/fixtures/registry/oci";
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    #[test]
+    fn anonymous_test() {
+        let pkg = Package::new("owner/tool", "1.0.0").unwrap();
+        let client = http_client::new(http_client::DefaultHttpClient::default(), TIMEOUT);
+        let data_path = format!("{}/tags.json", FIXTURES_PATH);
+        let _mock = mock_oci_tags("owner/tool", 200, &data_path);
+
+        let result = Oci::new(mockito::server_url()).get_latest_version(client, &pkg);
+
+        assert!(result.is_ok());
+        assert_eq!(result.expect("get result"), Some("v1.10.0".to_string()));
+    }
+
+    #[test]
+    fn docker_hub_token_flow_test() {
+        let pkg = Package::new("nginx", "1.0.0").unwrap();
+        let client = http_client::new(http_client::DefaultHttpClient::default(), TIMEOUT);
+        let data_path = format!("{}/tags.json", FIXTURES_PATH);
+
+        let _token = mock("GET", "/token")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("service".into(), "registry.docker.io".into()),
+                Matcher::UrlEncoded("scope".into(), "repository:library/nginx:pull".into()),
+            ]))
+            .with_status(200)
+            .with_body(r#"{"token":"anonymous-token","expires_in":300}"#)
+            .create();
+        let _tags = mock("GET", "/v2/library/nginx/tags/list")
+            .match_header("Authorization", "Bearer anonymous-token")
+            .with_status(200)
+            .with_body(std::fs::read_to_string(data_path).unwrap())
+            .create();
+
+        let result = Oci::docker_hub().get_latest_version(client, &pkg);
+
+        assert_eq!(result.expect("get result"), Some("v1.10.0".to_string()));
+    }
+
+    #[test]
+    fn failure_test() {
+        let pkg = Package::new("owner/missing", "1.0.0").unwrap();
+        let client = http_client::new(http_client::DefaultHttpClient::default(), TIMEOUT);
+        let data_path = format!("{}/not_found.json", FIXTURES_PATH);
+        let _mock = mock_oci_tags("owner/missing", 404, &data_path);
+
+        let result = Oci::new(mockito::server_url()).get_latest_version(client, &pkg);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn pagination_test() {
+        let pkg = Package::new("owner/paged", "1.0.0").unwrap();
+        let client = http_client::new(http_client::DefaultHttpClient::default(), TIMEOUT);
+
+        let _first = mock("GET", "/v2/owner/paged/tags/list")
+            .match_query(Matcher::Missing)
+            .with_status(200)
+            .with_header(
+                "Link",
+                r#"</v2/owner/paged/tags/list?last=1.9.0&n=2>; rel="next""#,
+            )
+            .with_body(r#"{"name":"owner/paged","tags":["1.8.0","1.9.0"]}"#)
+            .create();
+        let _second = mock("GET", "/v2/owner/paged/tags/list")
+            .match_query(Matcher::UrlEncoded("last".into(), "1.9.0".into()))
+            .with_status(200)
+            .with_body(r#"{"name":"owner/paged","tags":["2.0.0","latest"]}"#)
+            .create();
+
+        let result = Oci::new(mockito::server_url()).get_latest_version(client, &pkg);
+        assert_eq!(result.expect("get result"), Some("2.0.0".to_string()));
+    }
+
+    #[test]
+    fn cache_name_test() {
+        let ghcr = Oci::new("https://ghcr.io").cache_name();
+        assert!(ghcr.starts_with("oci-ghcr.io-"), "{ghcr}");
+        assert_ne!(Oci::new("https://registry.example.com").cache_name(), ghcr);
+    }
+
+    #[test]
+    fn next_link_test() {
+        assert_eq!(
+            next_link(r#"</v2/a/tags/list?last=b&n=10>; rel="next""#),
+            Some("/v2/a/tags/list?last=b&n=10")
+        );
+        assert_eq!(next_link(r#"</v2/a/tags/list?n=10>; rel="prev""#), None);
+
+        let oci = Oci::new("https://registry.example.com/");
+        assert_eq!(
+            oci.resolve("/v2/a/tags/list?last=b"),
+            "https://registry.example.com/v2/a/tags/list?last=b"
+        );
+        assert_eq!(
+            oci.resolve("https://other.example.com/x"),
+            "https://other.example.com/x"
+        );
+    }
+
+    #[test]
+    fn latest_tag_test() {
+        let tags = [
+            "latest",
+            "1.2",
+            "v1.9.0",
+            "1.11",
+            "1.10.0-alpine",
+            "sha-abc123",
+            "1.3.0",
+        ]
+        .map(String::from)
+        .to_vec();
+
+        assert_eq!(latest_tag(tags.clone(), false), Some("1.11.0".to_string()));
+        assert_eq!(latest_tag(tags, true), Some("1.11.0".to_string()));
+        assert_eq!(latest_tag(vec!["latest".to_string()], false), None);
+
+        let tags = ["1.9", "1.10.0-alpine"].map(String::from).to_vec();
+        assert_eq!(latest_tag(tags.clone(), false), Some("1.9.0".to_string()));
+        assert_eq!(latest_tag(tags, true), Some("1.10.0-alpine".to_string()));
+    }
+
+    #[test]
+    fn pad_test() {
+        assert_eq!(pad("1.2"), "1.2.0");
+        assert_eq!(pad("v1.2-alpine"), "v1.2.0-alpine");
+        assert_eq!(pad("1.2.3"), "1.2.3");
+        assert_eq!(pad("3"), "3");
+        assert_eq!(pad("sha-abc123"), "sha-abc123");
+    }
+}
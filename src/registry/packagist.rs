@@ -0,0 +1,114 @@
+use crate::{
+    http_client::{GenericHttpClient, HttpClient},
+    Package, Registry, Result, Version,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[cfg(not(test))]
+const REGISTRY_URL: &str = "https://repo.packagist.org";
+
+#[derive(Deserialize)]
+struct Response {
+    packages: HashMap<String, Vec<Release>>,
+}
+
+#[derive(Deserialize)]
+struct Release {
+    version: String,
+}
+
+/// The Packagist package registry for Composer packages.
+///
+/// Packages are named `vendor/package`. Pre-releases are only considered if the
+/// current version is a pre-release.
+pub struct Packagist;
+
+#[cfg(not(test))]
+fn get_base_url() -> String {
+    format!("{REGISTRY_URL}/p2")
+}
+
+#[cfg(test)]
+fn get_base_url() -> String {
+    format!("{}/p2", &mockito::server_url())
+}
+
+impl Registry for Packagist {
+    const NAME: &'static str = "packagist";
+
+    fn get_latest_version<T: HttpClient>(
+        &self,
+        http_client: GenericHttpClient<T>,
+        pkg: &Package,
+    ) -> Result<Option<String>> {
+        let name = pkg.to_string();
+        let url = format!("{}/{}.json", get_base_url(), name);
+        let mut resp = http_client.get::<Response>(&url)?;
+
+        let prereleases = !pkg.version().semver().pre.is_empty();
+
+        let latest = resp
+            .packages
+            .remove(&name)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|r| Version::parse(&r.version).ok().map(|v| (v, r.version)))
+            .filter(|(v, _)| prereleases || v.semver().pre.is_empty())
+            .max_by(|(a, _), (b, _)| a.cmp(b))
+            .map(|(_, raw)| raw);
+
+        Ok(latest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{http_client, test_helper::mock_packagist};
+    use std::time::Duration;
+
+    const PKG_NAME: &str = "vendor/package";
+    const FIXTURES_PATH: &str = "tests/fixtures/registry/packagist";
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    #[test]
+    fn failure_test() {
+        let pkg = Package::new("vendor/missing", "0.1.0").unwrap();
+        let client = http_client::new(http_client::DefaultHttpClient::default(), TIMEOUT);
+        let data_path = format!("{}/not_found.html", FIXTURES_PATH);
+        let _mock = mock_packagist(&pkg, 404, &data_path);
+
+        let result = Packagist.get_latest_version(client, &pkg);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn success_test() {
+        let pkg = Package::new(PKG_NAME, "2.0.0").unwrap();
+        let client = http_client::new(http_client::DefaultHttpClient::default(), TIMEOUT);
+        let data_path = format!("{}/package.json", FIXTURES_PATH);
+        let _mock = mock_packagist(&pkg, 200, &data_path);
+
+        let result = Packagist.get_latest_version(client, &pkg);
+
+        assert!(result.is_ok());
+        assert_eq!(result.expect("get result"), Some("v2.1.0".to_string()));
+    }
+
+    #[test]
+    fn prerelease_test() {
+        let pkg = Package::new(PKG_NAME, "2.0.0-alpha1").unwrap();
+        let client = http_client::new(http_client::DefaultHttpClient::default(), TIMEOUT);
+        let data_path = format!("{}/package.json", FIXTURES_PATH);
+        let _mock = mock_packagist(&pkg, 200, &data_path);
+
+        let result = Packagist.get_latest_version(client, &pkg);
+
+        assert!(result.is_ok());
+        assert_eq!(
+            result.expect("get result"),
+            Some("v2.2.0-beta1".to_string())
+        );
+    }
+}
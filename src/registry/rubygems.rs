@@ -0,0 +1,83 @@
+use crate::{
+    http_client::{GenericHttpClient, HttpClient},
+    Package, Registry, Result,
+};
+use serde::Deserialize;
+
+#[cfg(not(test))]
+const REGISTRY_URL: &str = "https://rubygems.org";
+
+#[derive(Deserialize)]
+struct Response {
+    version: String,
+}
+
+/// The RubyGems package registry.
+pub struct RubyGems;
+
+#[cfg(not(test))]
+fn get_base_url() -> String {
+    format!("{REGISTRY_URL}/api/v1/versions")
+}
+
+#[cfg(test)]
+fn get_base_url() -> String {
+    format!("{}/api/v1/versions", &mockito::server_url())
+}
+
+impl Registry for RubyGems {
+    const NAME: &'static str = "rubygems";
+
+    fn get_latest_version<T: HttpClient>(
+        &self,
+        http_client: GenericHttpClient<T>,
+        pkg: &Package,
+    ) -> Result<Option<String>> {
+        let url = format!("{}/{}/latest.json", get_base_url(), pkg);
+        let resp = http_client.get::<Response>(&url)?;
+
+        // RubyGems responds with a successful status and an `unknown` version for missing gems
+        if resp.version == "unknown" {
+            return Ok(None);
+        }
+
+        Ok(Some(resp.version))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{http_client, test_helper::mock_rubygems};
+    use std::time::Duration;
+
+    const PKG_NAME: &str = "rack";
+    const FIXTURES_PATH: &str = "tests/fixtures/registry/rubygems";
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    #[test]
+    fn not_found_test() {
+        let pkg = Package::new("missing", "0.1.0").unwrap();
+        let client = http_client::new(http_client::DefaultHttpClient::default(), TIMEOUT);
+        let data_path = format!("{}/not_found.json", FIXTURES_PATH);
+        let _mock = mock_rubygems(&pkg, 200, &data_path);
+
+        let result = RubyGems.get_latest_version(client, &pkg);
+
+        assert!(result.is_ok());
+        assert_eq!(result.expect("get result"), None);
+    }
+
+    #[test]
+    fn success_test() {
+        let pkg = Package::new(PKG_NAME, "2.2.0").unwrap();
+        let client = http_client::new(http_client::DefaultHttpClient::default(), TIMEOUT);
+        let data_path = format!("{}/latest.json", FIXTURES_PATH);
+        let _mock = mock_rubygems(&pkg, 200, &data_path);
+
+        let result = RubyGems.get_latest_version(client, &pkg);
+
+        assert!(result.is_ok());
+        assert_eq!(result.expect("get result"), Some("2.3.1".to_string()));
+    }
+}
@@ -0,0 +1,102 @@
+use crate::{Package, Result, Version, VersionScheme};
+use serde::{Deserialize, Serialize};
+
+/// A release of a package, as returned by a registry.
+///
+/// Most registries only provide the version, while registries such as
+/// [`Manifest`](crate::registry::Manifest) also provide download details.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct Release {
+    /// The version of the release.
+    pub version: String,
+    /// The oldest version that is still supported. Older versions must be updated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_supported_version: Option<String>,
+    /// A download URL for the current platform.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// A URL of the release notes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes_url: Option<String>,
+    /// The registry that answered, set by [`Fallback`](crate::registry::Fallback).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+impl Release {
+    /// Constructs a new `Release` with only a version.
+    pub fn new<V: Into<String>>(version: V) -> Self {
+        Self {
+            version: version.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Returns `true` if the release has more information than the version.
+    pub(crate) fn has_details(&self) -> bool {
+        self.min_supported_version.is_some()
+            || self.url.is_some()
+            || self.notes_url.is_some()
+            || self.source.is_some()
+    }
+}
+
+/// A new version together with the release it was found in.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Update {
+    version: Version,
+    release: Release,
+    required: bool,
+}
+
+impl Update {
+    pub(crate) fn new(version: Version, release: Release, required: bool) -> Self {
+        Self {
+            version,
+            release,
+            required,
+        }
+    }
+
+    /// Returns an update if the release is newer than the current version of the package.
+    pub(crate) fn check(
+        pkg: &Package,
+        release: Release,
+        scheme: VersionScheme,
+    ) -> Result<Option<Self>> {
+        let version = Version::parse_with_scheme(&release.version, scheme)?;
+        if &version <= pkg.version() {
+            return Ok(None);
+        }
+
+        // A minimum supported version that cannot be parsed does not require the update
+        let required = match &release.min_supported_version {
+            Some(v) => Version::parse_with_scheme(v, scheme).is_ok_and(|min| pkg.version() < &min),
+            None => false,
+        };
+
+        Ok(Some(Self::new(version, release, required)))
+    }
+
+    /// Returns the new version.
+    pub fn version(&self) -> &Version {
+        &self.version
+    }
+
+    /// Returns the release details provided by the registry.
+    pub fn release(&self) -> &Release {
+        &self.release
+    }
+
+    /// Returns `true` if the current version is older than the minimum supported version.
+    pub fn is_required(&self) -> bool {
+        self.required
+    }
+}
+
+impl From<Update> for Version {
+    fn from(update: Update) -> Self {
+        update.version
+    }
+}
@@ -1,30 +1,43 @@
 use crate::Package;
 use mockito::{mock, Mock};
 use once_cell::sync::Lazy;
-use std::{fs, panic, path::PathBuf, sync::Mutex};
-
-static LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::default());
-
-pub(crate) fn within_test_dir(f: fn(path: PathBuf)) {
-    // To avoid problems when working in parallel with the file system
-    let mutex = LOCK.lock().expect("unlock mutex");
-
-    let test_dir: PathBuf = std::env::temp_dir().join("update-informer-test");
-    fs::create_dir_all(&test_dir).expect("create test dir");
+use std::{
+    fs,
+    sync::{RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+
+static GLOBALS: Lazy<RwLock<()>> = Lazy::new(RwLock::default);
+
+/// Allows checks to run in parallel, but not while a test changes the global state.
+/// Take it before creating mocks, which hold their own lock until the end of the test.
+pub(crate) fn read_globals() -> RwLockReadGuard<'static, ()> {
+    // A panicking test must not break other tests
+    GLOBALS.read().unwrap_or_else(|e| e.into_inner())
+}
 
-    let result = panic::catch_unwind(|| {
-        let path: PathBuf = test_dir.join("crates-repo");
+/// Waits for running checks before a test changes the global state, e.g. the offline mode
+/// or environment variables.
+pub(crate) fn write_globals() -> RwLockWriteGuard<'static, ()> {
+    GLOBALS.write().unwrap_or_else(|e| e.into_inner())
+}
 
-        f(path);
-    });
+/// Sets an environment variable and removes it when dropped, even if the test panics.
+/// Hold [`write_globals()`] while it is set.
+#[cfg(any(feature = "npm", feature = "cargo-config", feature = "goproxy"))]
+pub(crate) struct EnvVar(&'static str);
 
-    fs::remove_dir_all(test_dir).expect("remove test dir");
+#[cfg(any(feature = "npm", feature = "cargo-config", feature = "goproxy"))]
+impl EnvVar {
+    pub(crate) fn set(key: &'static str, value: &str) -> Self {
+        std::env::set_var(key, value);
+        Self(key)
+    }
+}
 
-    if let Err(e) = result {
-        // If we panic while holding the mutex, it becomes poisoned, and future
-        // tests fail in a unexpected way. So release lock before the panic.
-        drop(mutex);
-        panic::resume_unwind(e);
+#[cfg(any(feature = "npm", feature = "cargo-config", feature = "goproxy"))]
+impl Drop for EnvVar {
+    fn drop(&mut self) {
+        std::env::remove_var(self.0);
     }
 }
 
@@ -36,6 +49,21 @@ pub(crate) fn mock_crates(pkg: &Package, status: usize, data_path: &str) -> (Moc
     (mock_http(&mock_path, status, &data), data)
 }
 
+#[cfg(feature = "crates")]
+pub(crate) fn mock_crates_index(pkg: &Package, status: usize, data_path: &str) -> Mock {
+    let mock_path = format!(
+        "/{}",
+        crate::registry::index_path(&pkg.to_string()).unwrap()
+    );
+    let data = fs::read_to_string(data_path).expect("read file to string");
+
+    mock("GET", mock_path.as_str())
+        .with_status(status)
+        .with_header("Content-Type", "text/plain")
+        .with_body(data)
+        .create()
+}
+
 #[cfg(feature = "github")]
 pub(crate) fn mock_github(pkg: &Package, status: usize, data_path: &str) -> (Mock, String) {
     let mock_path = format!("/repos/{}/releases/latest", pkg);
@@ -44,6 +72,38 @@ pub(crate) fn mock_github(pkg: &Package, status: usize, data_path: &str) -> (Moc
     (mock_http(&mock_path, status, &data), data)
 }
 
+#[cfg(feature = "goproxy")]
+pub(crate) fn mock_goproxy(module: &str, endpoint: &str, status: usize, data_path: &str) -> Mock {
+    let mock_path = format!("/{}/{}", module, endpoint);
+    let data = fs::read_to_string(data_path).expect("read file to string");
+
+    mock_http(&mock_path, status, &data)
+}
+
+#[cfg(feature = "hex")]
+pub(crate) fn mock_hex(pkg: &Package, status: usize, data_path: &str) -> (Mock, String) {
+    let mock_path = format!("/api/packages/{}", pkg);
+    let data = fs::read_to_string(data_path).expect("read file to string");
+
+    (mock_http(&mock_path, status, &data), data)
+}
+
+#[cfg(feature = "homebrew")]
+pub(crate) fn mock_homebrew(pkg: &Package, status: usize, data_path: &str) -> Mock {
+    let mock_path = format!("/api/formula/{}.json", pkg);
+    let data = fs::read_to_string(data_path).expect("read file to string");
+
+    mock_http(&mock_path, status, &data)
+}
+
+#[cfg(feature = "maven")]
+pub(crate) fn mock_maven(path: &str, status: usize, data_path: &str) -> (Mock, String) {
+    let mock_path = format!("/maven2/{}/maven-metadata.xml", path);
+    let data = fs::read_to_string(data_path).expect("read file to string");
+
+    (mock_http(&mock_path, status, &data), data)
+}
+
 #[cfg(feature = "npm")]
 pub(crate) fn mock_npm(pkg: &Package, status: usize, data_path: &str) -> (Mock, String) {
     let mock_path = format!("/{}/latest", pkg);
@@ -52,6 +112,33 @@ pub(crate) fn mock_npm(pkg: &Package, status: usize, data_path: &str) -> (Mock,
     (mock_http(&mock_path, status, &data), data)
 }
 
+#[cfg(feature = "nuget")]
+pub(crate) fn mock_nuget(pkg: &Package, status: usize, data_path: &str) -> (Mock, String) {
+    let mock_path = format!(
+        "/v3-flatcontainer/{}/index.json",
+        pkg.to_string().to_lowercase()
+    );
+    let data = fs::read_to_string(data_path).expect("read file to string");
+
+    (mock_http(&mock_path, status, &data), data)
+}
+
+#[cfg(feature = "oci")]
+pub(crate) fn mock_oci_tags(repository: &str, status: usize, data_path: &str) -> Mock {
+    let mock_path = format!("/v2/{}/tags/list", repository);
+    let data = fs::read_to_string(data_path).expect("read file to string");
+
+    mock_http(&mock_path, status, &data)
+}
+
+#[cfg(feature = "packagist")]
+pub(crate) fn mock_packagist(pkg: &Package, status: usize, data_path: &str) -> (Mock, String) {
+    let mock_path = format!("/p2/{}.json", pkg);
+    let data = fs::read_to_string(data_path).expect("read file to string");
+
+    (mock_http(&mock_path, status, &data), data)
+}
+
 #[cfg(feature = "pypi")]
 pub(crate) fn mock_pypi(pkg: &Package, status: usize, data_path: &str) -> (Mock, String) {
     let mock_path = format!("/pypi/{}/json", pkg);
@@ -60,6 +147,14 @@ pub(crate) fn mock_pypi(pkg: &Package, status: usize, data_path: &str) -> (Mock,
     (mock_http(&mock_path, status, &data), data)
 }
 
+#[cfg(feature = "rubygems")]
+pub(crate) fn mock_rubygems(pkg: &Package, status: usize, data_path: &str) -> (Mock, String) {
+    let mock_path = format!("/api/v1/versions/{}/latest.json", pkg);
+    let data = fs::read_to_string(data_path).expect("read file to string");
+
+    (mock_http(&mock_path, status, &data), data)
+}
+
 pub(crate) fn mock_http(path: &str, status: usize, body: &str) -> Mock {
     mock("GET", path)
         .with_status(status)
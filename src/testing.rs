@@ -0,0 +1,318 @@
+use crate::{
+    cache::Clock,
+    http_client::{HttpClient, HttpError, Request, Response},
+    Result,
+};
+use std::{
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+/// A request received by [`MockHttpClient`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct RecordedRequest {
+    /// The requested URL.
+    pub url: String,
+    /// The request headers, e.g. `User-Agent`.
+    pub headers: Vec<(String, String)>,
+    /// The time the request was allowed to take.
+    pub timeout: Duration,
+}
+
+impl RecordedRequest {
+    /// Returns the value of a header, ignoring the case of its name.
+    pub fn header(&self, key: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Answer {
+    Response(Response),
+    Error(String),
+}
+
+#[derive(Clone, Debug)]
+struct Route {
+    url: String,
+    answer: Answer,
+    delay: Duration,
+}
+
+impl Route {
+    /// Matches the URL exactly or, if the route ends with `*`, by prefix.
+    fn matches(&self, url: &str) -> bool {
+        match self.url.strip_suffix('*') {
+            Some(prefix) => url.starts_with(prefix),
+            None => self.url == url,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    routes: Vec<Route>,
+    requests: Vec<RecordedRequest>,
+}
+
+/// An HTTP client answering requests with canned responses, to test an application
+/// without network access.
+///
+/// Requests to URLs without a response get `404 Not Found`. All requests are recorded,
+/// and clones share the responses and the recorded requests, so a clone can be passed
+/// to the informer while the original is used for assertions.
+///
+/// # Examples
+///
+/// ```rust
+/// use update_informer::{http_client::Response, registry, testing::MockHttpClient, Check};
+///
+/// let client = MockHttpClient::new().on(
+///     "https://crates.io/api/v1/crates/my-app/versions",
+///     Response::new(200, r#"{"versions":[{"num":"0.2.0"}]}"#),
+/// );
+///
+/// let informer = update_informer::new(registry::Crates, "my-app", "0.1.0")
+///     .interval(std::time::Duration::ZERO)
+///     .http_client(client.clone());
+///
+/// let version = informer.check_version().unwrap().expect("new version");
+/// assert_eq!(version.to_string(), "v0.2.0");
+/// assert_eq!(client.requests().len(), 1);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct MockHttpClient {
+    state: Arc<Mutex<State>>,
+}
+
+impl MockHttpClient {
+    /// Constructs a new `MockHttpClient` without responses.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answers requests to the URL with the response. A URL ending with `*` matches
+    /// all URLs starting with it. Later responses take precedence.
+    pub fn on<U: Into<String>>(self, url: U, response: Response) -> Self {
+        self.route(url.into(), Answer::Response(response), Duration::ZERO)
+    }
+
+    /// Answers requests to the URL with the response after a delay. If the delay exceeds
+    /// the timeout of the request, the request fails after the timeout.
+    pub fn on_delayed<U: Into<String>>(self, url: U, response: Response, delay: Duration) -> Self {
+        self.route(url.into(), Answer::Response(response), delay)
+    }
+
+    /// Fails requests to the URL with [`HttpError::Connect`], e.g. a refused connection.
+    pub fn on_error<U: Into<String>, M: Into<String>>(self, url: U, message: M) -> Self {
+        self.route(url.into(), Answer::Error(message.into()), Duration::ZERO)
+    }
+
+    /// Returns the requests received so far.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.lock().requests.clone()
+    }
+
+    fn route(self, url: String, answer: Answer, delay: Duration) -> Self {
+        self.lock().routes.push(Route { url, answer, delay });
+        self
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        // A panicking test must not break the assertions of other tests
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl HttpClient for MockHttpClient {
+    fn send(&self, request: Request) -> Result<Response> {
+        let route = {
+            let mut state = self.lock();
+            state.requests.push(RecordedRequest {
+                url: request.url.to_string(),
+                headers: request
+                    .headers
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect(),
+                timeout: request.timeout,
+            });
+
+            state
+                .routes
+                .iter()
+                .rev()
+                .find(|route| route.matches(request.url))
+                .cloned()
+        };
+
+        let Some(route) = route else {
+            return Ok(Response::new(404, "Not Found"));
+        };
+
+        if route.delay > request.timeout {
+            thread::sleep(request.timeout);
+            return Err(HttpError::Connect(format!("request to {} timed out", request.url)).into());
+        }
+        thread::sleep(route.delay);
+
+        match route.answer {
+            Answer::Response(response) => Ok(response),
+            Answer::Error(message) => Err(HttpError::Connect(message).into()),
+        }
+    }
+}
+
+/// A clock that only moves when told to, to test when the interval expires.
+///
+/// Clones share the time, so a clone can be passed to the informer while the original is advanced.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::time::{Duration, SystemTime};
+/// use update_informer::{cache::MemoryStorage, registry, testing::MockClock, Check};
+///
+/// let storage = MemoryStorage::new();
+/// let clock = MockClock::new(SystemTime::UNIX_EPOCH);
+/// let informer = || {
+///     update_informer::new(registry::Crates, "my-app", "0.1.0")
+///         .cache_storage(storage.clone())
+///         .clock(clock.clone())
+/// };
+///
+/// // The first check only starts the interval
+/// assert_eq!(informer().check_version().unwrap(), None);
+///
+/// clock.advance(Duration::from_secs(60 * 60));
+/// assert_eq!(informer().check_version().unwrap(), None); // The cached version is returned
+/// ```
+#[derive(Clone, Debug)]
+pub struct MockClock {
+    now: Arc<Mutex<SystemTime>>,
+}
+
+impl MockClock {
+    /// Constructs a new `MockClock` showing the time.
+    pub fn new(now: SystemTime) -> Self {
+        Self {
+            now: Arc::new(Mutex::new(now)),
+        }
+    }
+
+    /// Moves the clock forward.
+    pub fn advance(&self, duration: Duration) {
+        *self.lock() += duration;
+    }
+
+    /// Sets the time of the clock.
+    pub fn set(&self, now: SystemTime) {
+        *self.lock() = now;
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, SystemTime> {
+        self.now.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> SystemTime {
+        *self.lock()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http_client::{self, RetryPolicy};
+
+    const URL: &str = "https://example.com/api/package";
+
+    fn client(mock: &MockHttpClient) -> http_client::GenericHttpClient<'static, MockHttpClient> {
+        http_client::new(mock.clone(), Duration::from_millis(500)).retry_policy(RetryPolicy::none())
+    }
+
+    #[test]
+    fn response_test() {
+        let mock = MockHttpClient::new().on(URL, Response::new(200, r#"{"version":"1.0.0"}"#));
+
+        let json: serde_json::Value = client(&mock).get(URL).expect("get json");
+        assert_eq!(json["version"], "1.0.0");
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].url, URL);
+        assert!(requests[0].header("user-agent").is_some());
+    }
+
+    #[test]
+    fn not_found_test() {
+        let mock = MockHttpClient::new().on("https://example.com/api/*", Response::new(500, ""));
+
+        let resp = client(&mock)
+            .get_response("https://example.com/other")
+            .unwrap();
+        assert_eq!(resp.status, 404);
+
+        let resp = client(&mock).get_response(URL).unwrap();
+        assert_eq!(resp.status, 500);
+    }
+
+    #[test]
+    fn error_test() {
+        let mock = MockHttpClient::new().on_error(URL, "connection refused");
+
+        let error = client(&mock).get_response(URL).unwrap_err();
+        assert_eq!(error.to_string(), "connection refused");
+        assert_eq!(
+            error.downcast_ref(),
+            Some(&HttpError::Connect("connection refused".to_string()))
+        );
+    }
+
+    #[test]
+    fn final_error_test() {
+        let mock = MockHttpClient::new()
+            .on(URL, Response::new(200, "x".repeat(100)))
+            .on(
+                "https://example.com/portal",
+                Response::new(200, "<p>").with_header("Content-Type", "text/html"),
+            );
+        let client = http_client::new(mock.clone(), Duration::from_millis(500)).max_body_size(10);
+
+        let error = client.get_text(URL).unwrap_err();
+        assert_eq!(error.downcast_ref(), Some(&HttpError::BodyTooLarge(10)));
+        assert_eq!(mock.requests().len(), 1);
+
+        let error = client.get_text("https://example.com/portal").unwrap_err();
+        assert_eq!(error.downcast_ref(), Some(&HttpError::Html));
+        assert_eq!(mock.requests().len(), 2);
+    }
+
+    #[test]
+    fn delay_test() {
+        let mock = MockHttpClient::new()
+            .on_delayed(URL, Response::new(200, "{}"), Duration::from_millis(50))
+            .on_delayed(
+                "https://example.com/slow",
+                Response::new(200, "{}"),
+                Duration::from_secs(5),
+            );
+
+        assert!(client(&mock).get_response(URL).is_ok());
+
+        let client = client(&mock);
+        assert!(client.get_response("https://example.com/slow").is_err());
+
+        let error = client.get_response(URL).unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<HttpError>(),
+            Some(&HttpError::DeadlineExceeded)
+        );
+    }
+}
@@ -1,9 +1,28 @@
 use crate::Result;
-use std::fmt::{Display, Formatter};
+use pep440::Pep440Version;
+use std::{
+    cmp::Ordering,
+    fmt::{Display, Formatter},
+};
+
+mod pep440;
+
+/// A versioning scheme used by a registry to parse and compare versions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum VersionScheme {
+    /// Semantic Versioning, e.g. `1.2.3-beta.1`.
+    #[default]
+    Semver,
+    /// Python package versions as defined by PEP 440, e.g. `1.0`, `2.1.0rc1` or `1.0.post1`.
+    Pep440,
+}
 
 /// A version representation.
-#[derive(Debug, Clone, PartialEq, PartialOrd, Eq)]
-pub struct Version(semver::Version);
+#[derive(Debug, Clone)]
+pub struct Version {
+    semver: semver::Version,
+    pep440: Option<Pep440Version>,
+}
 
 impl Version {
     pub(crate) fn parse<V: AsRef<str>>(value: V) -> Result<Self> {
@@ -11,37 +30,89 @@ impl Version {
         let v = value.strip_prefix('v').unwrap_or(value);
         let version = semver::Version::parse(v)?;
 
-        Ok(Self(version))
+        Ok(Self::from(version))
+    }
+
+    pub(crate) fn parse_with_scheme<V: AsRef<str>>(
+        value: V,
+        scheme: VersionScheme,
+    ) -> Result<Self> {
+        match scheme {
+            VersionScheme::Semver => Self::parse(value),
+            VersionScheme::Pep440 => {
+                let version = Pep440Version::parse(value.as_ref())?;
+
+                Ok(Self {
+                    semver: version.to_semver(),
+                    pep440: Some(version),
+                })
+            }
+        }
     }
 
     /// Returns `semver::Version`.
+    ///
+    /// For PEP 440 versions this is the closest semantic version, e.g. `2.1.0-rc.1` for `2.1.0rc1`.
     pub fn semver(&self) -> &semver::Version {
-        &self.0
+        &self.semver
+    }
+}
+
+impl From<semver::Version> for Version {
+    fn from(version: semver::Version) -> Self {
+        Self {
+            semver: version,
+            pep440: None,
+        }
     }
 }
 
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (&self.pep440, &other.pep440) {
+            (Some(a), Some(b)) => a.cmp(b),
+            _ => self.semver.cmp(&other.semver),
+        }
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Version {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Version {}
+
 impl Display for Version {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "v{}", self.0)
+        match &self.pep440 {
+            Some(version) => write!(f, "v{version}"),
+            None => write!(f, "v{}", self.semver),
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::Version;
+    use crate::{Version, VersionScheme};
 
     #[test]
     fn parse_str_version_test() {
         let version1 = Version::parse("0.1.0");
-        let version2 = Version {
-            0: semver::Version {
-                major: 0,
-                minor: 1,
-                patch: 0,
-                pre: Default::default(),
-                build: Default::default(),
-            },
-        };
+        let version2 = Version::from(semver::Version {
+            major: 0,
+            minor: 1,
+            patch: 0,
+            pre: Default::default(),
+            build: Default::default(),
+        });
 
         assert!(version1.is_ok());
         assert_eq!(version1.unwrap(), version2);
@@ -50,32 +121,29 @@ mod tests {
     #[test]
     fn parse_string_version_test() {
         let version1 = Version::parse(String::from("0.1.0"));
-        let version2 = Version {
-            0: semver::Version {
-                major: 0,
-                minor: 1,
-                patch: 0,
-                pre: Default::default(),
-                build: Default::default(),
-            },
-        };
+        let version2 = Version::from(semver::Version {
+            major: 0,
+            minor: 1,
+            patch: 0,
+            pre: Default::default(),
+            build: Default::default(),
+        });
 
         assert!(version1.is_ok());
         assert_eq!(version1.unwrap(), version2);
     }
 
     #[test]
+    #[allow(clippy::needless_borrows_for_generic_args)]
     fn parse_amp_string_version_test() {
         let version1 = Version::parse(&String::from("0.1.0"));
-        let version2 = Version {
-            0: semver::Version {
-                major: 0,
-                minor: 1,
-                patch: 0,
-                pre: Default::default(),
-                build: Default::default(),
-            },
-        };
+        let version2 = Version::from(semver::Version {
+            major: 0,
+            minor: 1,
+            patch: 0,
+            pre: Default::default(),
+            build: Default::default(),
+        });
 
         assert!(version1.is_ok());
         assert_eq!(version1.unwrap(), version2);
@@ -84,15 +152,13 @@ mod tests {
     #[test]
     fn parse_version_with_prefix_test() {
         let version1 = Version::parse("v0.1.0");
-        let version2 = Version {
-            0: semver::Version {
-                major: 0,
-                minor: 1,
-                patch: 0,
-                pre: Default::default(),
-                build: Default::default(),
-            },
-        };
+        let version2 = Version::from(semver::Version {
+            major: 0,
+            minor: 1,
+            patch: 0,
+            pre: Default::default(),
+            build: Default::default(),
+        });
 
         assert!(version1.is_ok());
         assert_eq!(version1.unwrap(), version2);
@@ -113,4 +179,24 @@ mod tests {
         let version = version.unwrap();
         assert_eq!(version.semver().pre.to_string(), "canary");
     }
+
+    #[test]
+    fn parse_pep440_version_test() {
+        let version = Version::parse_with_scheme("2.1.0rc1", VersionScheme::Pep440);
+        assert!(version.is_ok());
+
+        let version = version.unwrap();
+        assert_eq!(version.to_string(), "v2.1.0rc1");
+        assert_eq!(version.semver().to_string(), "2.1.0-rc.1");
+    }
+
+    #[test]
+    fn compare_pep440_versions_test() {
+        let parse = |v| Version::parse_with_scheme(v, VersionScheme::Pep440).unwrap();
+
+        assert!(parse("1.0") < parse("1.0.post1"));
+        assert!(parse("2.1.0rc1") < parse("2.1.0"));
+        assert!(parse("2023.12") < parse("2024.3"));
+        assert_eq!(parse("1.0"), parse("1.0.0"));
+    }
 }
@@ -0,0 +1,490 @@
+use crate::Result;
+use std::{
+    cmp::Ordering,
+    fmt::{Display, Formatter},
+};
+
+/// A pre-release phase, ordered as defined by PEP 440.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum PreKind {
+    Alpha,
+    Beta,
+    Rc,
+}
+
+impl PreKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            PreKind::Alpha => "a",
+            PreKind::Beta => "b",
+            PreKind::Rc => "rc",
+        }
+    }
+}
+
+/// A segment of a local version label, e.g. `ubuntu` or `1` in `1.0+ubuntu.1`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum LocalSegment {
+    Number(u64),
+    String(String),
+}
+
+impl Ord for LocalSegment {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Numeric segments always sort after alphanumeric ones
+        match (self, other) {
+            (LocalSegment::Number(a), LocalSegment::Number(b)) => a.cmp(b),
+            (LocalSegment::String(a), LocalSegment::String(b)) => a.cmp(b),
+            (LocalSegment::Number(_), LocalSegment::String(_)) => Ordering::Greater,
+            (LocalSegment::String(_), LocalSegment::Number(_)) => Ordering::Less,
+        }
+    }
+}
+
+impl PartialOrd for LocalSegment {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// A version in the format used by Python packages (PEP 440), e.g. `1.0`, `2.1.0rc1`,
+/// `1.0.post1` or `1!2.0.dev3+local.1`.
+#[derive(Debug, Clone)]
+pub(crate) struct Pep440Version {
+    epoch: u64,
+    release: Vec<u64>,
+    pre: Option<(PreKind, u64)>,
+    post: Option<u64>,
+    dev: Option<u64>,
+    local: Vec<LocalSegment>,
+}
+
+impl Pep440Version {
+    /// Parses a version leniently, accepting the alternative spellings allowed by PEP 440.
+    pub(crate) fn parse(value: &str) -> Result<Self> {
+        Parser::new(value)
+            .parse()
+            .ok_or_else(|| format!("invalid PEP 440 version: {value}").into())
+    }
+
+    /// Maps the version to the closest `semver::Version`.
+    ///
+    /// The first three release segments become major, minor and patch. Pre-releases and
+    /// development releases become pre-release identifiers (`a.1`, `rc.2`, `dev.3`), while
+    /// the epoch, extra release segments, post-releases and local labels are kept as
+    /// build metadata. The mapping is lossy, so comparisons are done on the PEP 440 form.
+    pub(crate) fn to_semver(&self) -> semver::Version {
+        let segment = |i: usize| self.release.get(i).copied().unwrap_or(0);
+
+        let mut pre = Vec::new();
+        if let Some((kind, n)) = self.pre {
+            pre.push(format!("{}.{n}", kind.as_str()));
+        }
+        if let Some(n) = self.dev {
+            pre.push(format!("dev.{n}"));
+        }
+
+        let mut build = Vec::new();
+        if self.epoch != 0 {
+            build.push(format!("epoch.{}", self.epoch));
+        }
+        if self.release.len() > 3 {
+            let extra: Vec<_> = self.release[3..].iter().map(u64::to_string).collect();
+            build.push(format!("release.{}", extra.join(".")));
+        }
+        if let Some(n) = self.post {
+            build.push(format!("post.{n}"));
+        }
+        if !self.local.is_empty() {
+            build.push(format!("local.{}", self.local_label()));
+        }
+
+        semver::Version {
+            major: segment(0),
+            minor: segment(1),
+            patch: segment(2),
+            pre: semver::Prerelease::new(&pre.join(".")).unwrap_or_default(),
+            build: semver::BuildMetadata::new(&build.join(".")).unwrap_or_default(),
+        }
+    }
+
+    fn local_label(&self) -> String {
+        self.local
+            .iter()
+            .map(|segment| match segment {
+                LocalSegment::Number(n) => n.to_string(),
+                LocalSegment::String(s) => s.clone(),
+            })
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+
+    /// Release segments without trailing zeros, so that `1.0` equals `1.0.0`.
+    fn trimmed_release(&self) -> &[u64] {
+        let len = self
+            .release
+            .iter()
+            .rposition(|&n| n != 0)
+            .map_or(0, |pos| pos + 1);
+
+        &self.release[..len]
+    }
+}
+
+/// A value that sorts before (`Min`) or after (`Max`) any given value.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Bound<T> {
+    Min,
+    Value(T),
+    Max,
+}
+
+impl Ord for Pep440Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        fn key(v: &Pep440Version) -> (Bound<(PreKind, u64)>, Bound<u64>, Bound<u64>) {
+            let pre = match (v.pre, v.post, v.dev) {
+                // A development release of a final release sorts before its pre-releases
+                (None, None, Some(_)) => Bound::Min,
+                (None, _, _) => Bound::Max,
+                (Some(pre), _, _) => Bound::Value(pre),
+            };
+            let post = v.post.map_or(Bound::Min, Bound::Value);
+            let dev = v.dev.map_or(Bound::Max, Bound::Value);
+
+            (pre, post, dev)
+        }
+
+        self.epoch
+            .cmp(&other.epoch)
+            .then_with(|| self.trimmed_release().cmp(other.trimmed_release()))
+            .then_with(|| key(self).cmp(&key(other)))
+            .then_with(|| self.local.cmp(&other.local))
+    }
+}
+
+impl PartialOrd for Pep440Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Pep440Version {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Pep440Version {}
+
+impl Display for Pep440Version {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.epoch != 0 {
+            write!(f, "{}!", self.epoch)?;
+        }
+
+        let release: Vec<_> = self.release.iter().map(u64::to_string).collect();
+        write!(f, "{}", release.join("."))?;
+
+        if let Some((kind, n)) = self.pre {
+            write!(f, "{}{n}", kind.as_str())?;
+        }
+        if let Some(n) = self.post {
+            write!(f, ".post{n}")?;
+        }
+        if let Some(n) = self.dev {
+            write!(f, ".dev{n}")?;
+        }
+        if !self.local.is_empty() {
+            write!(f, "+{}", self.local_label())?;
+        }
+
+        Ok(())
+    }
+}
+
+struct Parser {
+    input: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn new(value: &str) -> Self {
+        Self {
+            input: value.trim().to_lowercase().chars().collect(),
+            pos: 0,
+        }
+    }
+
+    fn parse(mut self) -> Option<Pep440Version> {
+        self.eat('v');
+
+        let mut epoch = 0;
+        let mut release = vec![self.number()?];
+        if self.eat('!') {
+            epoch = release[0];
+            release[0] = self.number()?;
+        }
+        while self.peek() == Some('.') && self.peek_at(1).is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+            release.push(self.number()?);
+        }
+
+        let pre = self.pre();
+        let post = self.post();
+        let dev = self.dev();
+        let local = self.local()?;
+
+        if self.pos != self.input.len() {
+            return None;
+        }
+
+        Some(Pep440Version {
+            epoch,
+            release,
+            pre,
+            post,
+            dev,
+            local,
+        })
+    }
+
+    fn pre(&mut self) -> Option<(PreKind, u64)> {
+        const SPELLINGS: [(&str, PreKind); 8] = [
+            ("alpha", PreKind::Alpha),
+            ("a", PreKind::Alpha),
+            ("beta", PreKind::Beta),
+            ("b", PreKind::Beta),
+            ("preview", PreKind::Rc),
+            ("pre", PreKind::Rc),
+            ("rc", PreKind::Rc),
+            ("c", PreKind::Rc),
+        ];
+
+        let start = self.pos;
+        self.separator();
+
+        for (spelling, kind) in SPELLINGS {
+            if self.eat_str(spelling) {
+                return Some((kind, self.optional_number()));
+            }
+        }
+
+        self.pos = start;
+        None
+    }
+
+    fn post(&mut self) -> Option<u64> {
+        let start = self.pos;
+
+        // An implicit post-release, e.g. `1.0-1`
+        if self.eat('-') && self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            return self.number();
+        }
+        self.pos = start;
+
+        self.separator();
+        for spelling in ["post", "rev", "r"] {
+            if self.eat_str(spelling) {
+                return Some(self.optional_number());
+            }
+        }
+
+        self.pos = start;
+        None
+    }
+
+    fn dev(&mut self) -> Option<u64> {
+        let start = self.pos;
+        self.separator();
+
+        if self.eat_str("dev") {
+            return Some(self.optional_number());
+        }
+
+        self.pos = start;
+        None
+    }
+
+    fn local(&mut self) -> Option<Vec<LocalSegment>> {
+        if !self.eat('+') {
+            return Some(Vec::new());
+        }
+
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'))
+        {
+            self.pos += 1;
+        }
+
+        let label: String = self.input[start..self.pos].iter().collect();
+        label
+            .split(['.', '-', '_'])
+            .map(|segment| {
+                if segment.is_empty() {
+                    None
+                } else if let Ok(n) = segment.parse() {
+                    Some(LocalSegment::Number(n))
+                } else {
+                    Some(LocalSegment::String(segment.to_string()))
+                }
+            })
+            .collect()
+    }
+
+    /// Skips an optional `.`, `-` or `_` separator.
+    fn separator(&mut self) {
+        if matches!(self.peek(), Some('.' | '-' | '_')) {
+            self.pos += 1;
+        }
+    }
+
+    /// Parses an optional number preceded by an optional separator, defaulting to zero.
+    fn optional_number(&mut self) -> u64 {
+        let start = self.pos;
+        self.separator();
+
+        match self.number() {
+            Some(n) => n,
+            None => {
+                self.pos = start;
+                0
+            }
+        }
+    }
+
+    fn number(&mut self) -> Option<u64> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+
+        if start == self.pos {
+            return None;
+        }
+
+        self.input[start..self.pos]
+            .iter()
+            .collect::<String>()
+            .parse()
+            .ok()
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.peek_at(0)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<char> {
+        self.input.get(self.pos + offset).copied()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            return true;
+        }
+
+        false
+    }
+
+    fn eat_str(&mut self, s: &str) -> bool {
+        let len = s.chars().count();
+        let matches = self.input.len() >= self.pos + len
+            && self.input[self.pos..self.pos + len]
+                .iter()
+                .copied()
+                .eq(s.chars());
+
+        if matches {
+            self.pos += len;
+        }
+
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(value: &str) -> Pep440Version {
+        Pep440Version::parse(value).expect("parse version")
+    }
+
+    #[test]
+    fn parse_test() {
+        assert_eq!(parse("1.0").to_string(), "1.0");
+        assert_eq!(parse("v2024.3").to_string(), "2024.3");
+        assert_eq!(parse("2.1.0rc1").to_string(), "2.1.0rc1");
+        assert_eq!(parse("1.0.post1").to_string(), "1.0.post1");
+        assert_eq!(
+            parse("1!2.0.dev3+Ubuntu-1").to_string(),
+            "1!2.0.dev3+ubuntu.1"
+        );
+    }
+
+    #[test]
+    fn parse_alternative_spellings_test() {
+        assert_eq!(parse("1.0-alpha.1").to_string(), "1.0a1");
+        assert_eq!(parse("1.0beta").to_string(), "1.0b0");
+        assert_eq!(parse("1.0c2").to_string(), "1.0rc2");
+        assert_eq!(parse("1.0-preview_3").to_string(), "1.0rc3");
+        assert_eq!(parse("1.0-1").to_string(), "1.0.post1");
+        assert_eq!(parse("1.0.rev2").to_string(), "1.0.post2");
+        assert_eq!(parse("1.0-dev").to_string(), "1.0.dev0");
+    }
+
+    #[test]
+    fn parse_invalid_test() {
+        assert!(Pep440Version::parse("").is_err());
+        assert!(Pep440Version::parse("one").is_err());
+        assert!(Pep440Version::parse("1.0foo").is_err());
+        assert!(Pep440Version::parse("1.0+").is_err());
+    }
+
+    #[test]
+    fn ordering_test() {
+        let versions = [
+            "1.0.dev1",
+            "1.0a1.dev1",
+            "1.0a1",
+            "1.0a2",
+            "1.0b1",
+            "1.0rc1",
+            "1.0",
+            "1.0+local",
+            "1.0+local.1",
+            "1.0.post1.dev1",
+            "1.0.post1",
+            "1.1",
+            "2024.3",
+            "1!0.1",
+        ];
+
+        for pair in versions.windows(2) {
+            assert!(parse(pair[0]) < parse(pair[1]), "{} < {}", pair[0], pair[1]);
+        }
+    }
+
+    #[test]
+    fn equality_test() {
+        assert_eq!(parse("1.0"), parse("1.0.0"));
+        assert_eq!(parse("1.0a"), parse("1.0.alpha0"));
+        assert_ne!(parse("1.0"), parse("1.0.post0"));
+    }
+
+    #[test]
+    fn to_semver_test() {
+        assert_eq!(parse("1.0").to_semver().to_string(), "1.0.0");
+        assert_eq!(parse("2.1.0rc1").to_semver().to_string(), "2.1.0-rc.1");
+        assert_eq!(
+            parse("1.0a1.dev2").to_semver().to_string(),
+            "1.0.0-a.1.dev.2"
+        );
+        assert_eq!(
+            parse("1!1.2.3.4.post5+abc").to_semver().to_string(),
+            "1.2.3+epoch.1.release.4.post.5.local.abc"
+        );
+    }
+}
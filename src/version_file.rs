@@ -1,192 +1,193 @@
-use crate::{Package, Result};
-use std::{
-    fs,
-    io::{self, ErrorKind},
-    path::PathBuf,
-    time::Duration,
+use crate::{
+    cache::{CacheEntry, CacheStorage, Clock},
+    Package, Release, Result,
 };
+use std::time::Duration;
 
-#[derive(Debug, PartialEq)]
 pub(crate) struct VersionFile<'a> {
-    path: PathBuf,
+    key: String,
     version: &'a str,
+    storage: &'a dyn CacheStorage,
+    clock: &'a dyn Clock,
 }
 
 impl<'a> VersionFile<'a> {
-    pub(crate) fn new(registry: &str, pkg: &Package, version: &'a str) -> Result<Self> {
-        let file_name = format!("{}-{}", registry, pkg.name());
-        let path = cache_path()?.join(file_name);
-
-        Ok(Self { path, version })
+    pub(crate) fn new(
+        registry: &str,
+        pkg: &Package,
+        version: &'a str,
+        storage: &'a dyn CacheStorage,
+        clock: &'a dyn Clock,
+    ) -> Self {
+        let key = format!("{}-{}", registry, pkg.name());
+
+        Self {
+            key,
+            version,
+            storage,
+            clock,
+        }
     }
 
     pub(crate) fn last_modified(&self) -> Result<Duration> {
-        let metadata = match fs::metadata(&self.path) {
-            Ok(meta) => meta,
-            Err(e) if e.kind() == ErrorKind::NotFound => {
-                self.write_version(self.version)?;
-                return Ok(Duration::ZERO);
-            }
-            Err(e) => return Err(e.into()),
+        let Some(entry) = self.storage.read(&self.key)? else {
+            self.write_version(self.version)?;
+            return Ok(Duration::ZERO);
         };
 
-        let last_modified = metadata.modified()?.elapsed();
+        let last_modified = self.clock.now().duration_since(entry.modified);
         Ok(last_modified.unwrap_or_default())
     }
 
-    pub(crate) fn recreate_file(&self) -> io::Result<()> {
-        fs::remove_file(&self.path)?;
+    /// Restarts the interval with the current version.
+    pub(crate) fn recreate_file(&self) -> Result<()> {
         self.write_version(self.version)
     }
 
-    pub(crate) fn write_version<V: AsRef<str>>(&self, version: V) -> io::Result<()> {
-        fs::write(&self.path, version.as_ref())
+    pub(crate) fn write_version<V: AsRef<str>>(&self, version: V) -> Result<()> {
+        let entry = CacheEntry::new(version.as_ref(), self.clock.now());
+        self.storage.write(&self.key, entry)
     }
 
-    pub(crate) fn get_version(&self) -> io::Result<String> {
-        fs::read_to_string(&self.path)
+    pub(crate) fn get_version(&self) -> Result<String> {
+        match self.storage.read(&self.key)? {
+            Some(entry) => Ok(entry.contents),
+            None => Err(format!("no cached version of {}", self.key).into()),
+        }
     }
-}
 
-#[cfg(not(test))]
-fn cache_path() -> Result<PathBuf> {
-    use etcetera::BaseStrategy;
-    let base_dir =
-        etcetera::choose_base_strategy().map_err(|_| "Unable to find cache directory")?;
-    let directory = base_dir.cache_dir().join("update-informer-rs");
-    fs::create_dir_all(&directory)?;
-    Ok(directory)
-}
+    /// Writes only the version unless the release has more details, which are stored as JSON.
+    pub(crate) fn write_release(&self, release: &Release) -> Result<()> {
+        if !release.has_details() {
+            return self.write_version(&release.version);
+        }
 
-#[cfg(test)]
-fn cache_path() -> Result<PathBuf> {
-    Ok(std::env::temp_dir().join("update-informer-test"))
+        self.write_version(serde_json::to_string(release)?)
+    }
+
+    pub(crate) fn get_release(&self) -> Result<Release> {
+        let contents = self.get_version()?;
+
+        if contents.trim_start().starts_with('{') {
+            return Ok(serde_json::from_str(&contents)?);
+        }
+
+        Ok(Release::new(contents))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_helper::within_test_dir;
-
-    #[test]
-    fn new_test() {
-        let version = "0.1.0";
-        let pkg = Package::new("repo", version).unwrap();
-        let version_file1 = VersionFile::new("myreg", &pkg, version).unwrap();
-        let version_file2 = VersionFile {
-            path: cache_path().unwrap().join("myreg-repo"),
-            version: "0.1.0",
-        };
+    use crate::{cache::MemoryStorage, testing::MockClock};
+    use std::time::SystemTime;
+
+    const KEY: &str = "crates-repo";
+
+    fn version_file<'a>(
+        version: &'a str,
+        storage: &'a MemoryStorage,
+        clock: &'a MockClock,
+    ) -> VersionFile<'a> {
+        let pkg = Package::new("repo", "0.1.0").unwrap();
+        VersionFile::new("crates", &pkg, version, storage, clock)
+    }
 
-        assert_eq!(version_file1, version_file2);
+    fn contents(storage: &MemoryStorage) -> String {
+        storage.read(KEY).unwrap().expect("get entry").contents
     }
 
     #[test]
-    fn create_version_file_twice_test() {
-        let version = "0.1.0";
-        let pkg = Package::new("repo", version).unwrap();
-        let version_file1 = VersionFile::new("reg", &pkg, version).expect("create version file");
-        let version_file2 = VersionFile::new("reg", &pkg, version).expect("create version file");
-        assert_eq!(version_file1, version_file2);
+    fn new_test() {
+        let (storage, clock) = (MemoryStorage::new(), MockClock::new(SystemTime::UNIX_EPOCH));
+        let pkg = Package::new("owner/repo", "0.1.0").unwrap();
+        let version_file = VersionFile::new("myreg", &pkg, "0.1.0", &storage, &clock);
+
+        assert_eq!(version_file.key, "myreg-owner-repo");
     }
 
     #[test]
     fn last_modified_file_exists_test() {
-        within_test_dir(|path| {
-            fs::write(&path, "0.1.0").expect("creates test file");
-
-            let version_file = VersionFile {
-                path,
-                version: "0.1.0",
-            };
-
-            let last_modified = version_file.last_modified();
-            assert!(last_modified.is_ok());
-            assert!(!last_modified.unwrap().is_zero());
-        });
+        let (storage, clock) = (MemoryStorage::new(), MockClock::new(SystemTime::UNIX_EPOCH));
+        let entry = CacheEntry::new("0.1.0", clock.now());
+        storage.write(KEY, entry).expect("write entry");
+        clock.advance(Duration::from_secs(60));
+
+        let version_file = version_file("0.1.0", &storage, &clock);
+        let last_modified = version_file.last_modified();
+        assert_eq!(last_modified.unwrap(), Duration::from_secs(60));
     }
 
     #[test]
     fn last_modified_file_not_exists_test() {
-        within_test_dir(|path| {
-            let version_file = VersionFile {
-                path: path.clone(),
-                version: "0.1.0",
-            };
+        let (storage, clock) = (MemoryStorage::new(), MockClock::new(SystemTime::UNIX_EPOCH));
+        let version_file = version_file("0.1.0", &storage, &clock);
 
-            let last_modified = version_file.last_modified();
-            assert!(last_modified.is_ok());
-            assert!(last_modified.unwrap().is_zero());
-
-            let version = fs::read_to_string(&path).expect("read test file");
-            assert_eq!(version, "0.1.0");
-        });
+        let last_modified = version_file.last_modified();
+        assert!(last_modified.unwrap().is_zero());
+        assert_eq!(contents(&storage), "0.1.0");
     }
 
     #[test]
     fn recreate_file_test() {
-        within_test_dir(|path| {
-            fs::write(&path, "0.1.0").expect("creates test file");
-
-            let version_file = VersionFile {
-                path: path.clone(),
-                version: "1.0.0",
-            };
+        let (storage, clock) = (MemoryStorage::new(), MockClock::new(SystemTime::UNIX_EPOCH));
+        let entry = CacheEntry::new("0.1.0", clock.now());
+        storage.write(KEY, entry).expect("write entry");
+        clock.advance(Duration::from_secs(60));
 
-            let result = version_file.recreate_file();
-            assert!(result.is_ok());
+        let version_file = version_file("1.0.0", &storage, &clock);
+        let result = version_file.recreate_file();
+        assert!(result.is_ok());
 
-            let version = fs::read_to_string(&path).expect("read test file");
-            assert_eq!(version, "1.0.0");
-        });
+        assert_eq!(contents(&storage), "1.0.0");
+        assert!(version_file.last_modified().unwrap().is_zero());
     }
 
     #[test]
     fn write_version_test() {
-        within_test_dir(|path| {
-            fs::write(&path, "1.0.0").expect("creates test file");
-
-            let version_file = VersionFile {
-                path: path.clone(),
-                version: "1.0.0",
-            };
+        let (storage, clock) = (MemoryStorage::new(), MockClock::new(SystemTime::UNIX_EPOCH));
+        let version_file = version_file("1.0.0", &storage, &clock);
 
-            let result = version_file.write_version("2.0.0");
-            assert!(result.is_ok());
+        let result = version_file.write_version("2.0.0");
+        assert!(result.is_ok());
+        assert_eq!(contents(&storage), "2.0.0");
+    }
 
-            let version = fs::read_to_string(&path).expect("read test file");
-            assert_eq!(version, "2.0.0");
-        });
+    #[test]
+    fn write_release_test() {
+        let (storage, clock) = (MemoryStorage::new(), MockClock::new(SystemTime::UNIX_EPOCH));
+        let version_file = version_file("1.0.0", &storage, &clock);
+
+        let release = Release::new("2.0.0");
+        version_file.write_release(&release).expect("write release");
+        assert_eq!(contents(&storage), "2.0.0");
+        assert_eq!(version_file.get_release().expect("get release"), release);
+
+        let release = Release {
+            url: Some("https://example.com/tool.tar.gz".to_string()),
+            ..Release::new("2.0.0")
+        };
+        version_file.write_release(&release).expect("write release");
+        assert_eq!(version_file.get_release().expect("get release"), release);
     }
 
     #[test]
     fn get_version_file_exists_test() {
-        within_test_dir(|path| {
-            fs::write(&path, "1.0.0").expect("creates test file");
+        let (storage, clock) = (MemoryStorage::new(), MockClock::new(SystemTime::UNIX_EPOCH));
+        storage
+            .write(KEY, CacheEntry::new("1.0.0", clock.now()))
+            .expect("write entry");
 
-            let version_file = VersionFile {
-                path: path.clone(),
-                version: "1.0.0",
-            };
-
-            let result = version_file.get_version();
-            assert!(result.is_ok());
-
-            let version = fs::read_to_string(&path).expect("read test file");
-            assert_eq!(version, "1.0.0");
-        });
+        let version_file = version_file("1.0.0", &storage, &clock);
+        assert_eq!(version_file.get_version().unwrap(), "1.0.0");
     }
 
     #[test]
     fn get_version_file_not_exists_test() {
-        within_test_dir(|path| {
-            let version_file = VersionFile {
-                path: path.clone(),
-                version: "1.0.0",
-            };
-
-            let result = version_file.get_version();
-            assert!(result.is_err());
-        });
+        let (storage, clock) = (MemoryStorage::new(), MockClock::new(SystemTime::UNIX_EPOCH));
+        let version_file = version_file("1.0.0", &storage, &clock);
+
+        let result = version_file.get_version();
+        assert!(result.is_err());
     }
 }
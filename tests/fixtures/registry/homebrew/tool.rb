@@ -0,0 +1,17 @@
+class Tool < Formula
+  desc "Command-line tool"
+  homepage "https://github.com/owner/tool"
+  url "https://github.com/owner/tool/archive/refs/tags/v1.3.tar.gz"
+  sha256 "0f1e2d3c4b5a69788796a5b4c3d2e1f00f1e2d3c4b5a69788796a5b4c3d2e1f0"
+  license "MIT"
+
+  depends_on "rust" => :build
+
+  def install
+    system "cargo", "install", *std_cargo_args
+  end
+
+  test do
+    assert_match version.to_s, shell_output("#{bin}/tool --version")
+  end
+end